@@ -22,9 +22,9 @@ fn main() {
             // .warnings(true)
             .flag("-O1")
             .flag("-w")
-            .file(&format!("{}/{}.cpp", world_src, file_name))
+            .file(format!("{}/{}.cpp", world_src, file_name))
             .include(world_src)
-            .compile(&format!("{}", file_name));
+            .compile(file_name);
     }
 }
 
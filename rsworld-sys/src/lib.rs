@@ -1,8 +1,8 @@
-use std::os::raw::{c_int, c_double};
+use std::os::raw::{c_int, c_uint, c_double};
 
 // CheapTrick
 #[repr(C)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CheapTrickOption {
     pub q1:       c_double,
     pub f0_floor: c_double,
@@ -11,16 +11,16 @@ pub struct CheapTrickOption {
 
 impl CheapTrickOption {
     pub fn new(fs: c_int) -> Self {
+        let mut option = std::mem::MaybeUninit::<CheapTrickOption>::uninit();
         unsafe {
-            let mut option = std::mem::MaybeUninit::uninit().assume_init();
-            InitializeCheapTrickOption(fs, &mut option as *mut _);
-            option
+            InitializeCheapTrickOption(fs, option.as_mut_ptr());
+            option.assume_init()
         }
     }
 }
 
 #[link(name = "cheaptrick")]
-extern {
+extern "C" {
     pub fn CheapTrick(x:                  *const c_double,
                       x_length:           c_int,
                       fs:                 c_int,
@@ -32,12 +32,14 @@ extern {
     pub fn InitializeCheapTrickOption(fs:     c_int,
                                       option: *mut CheapTrickOption);
     pub fn GetFFTSizeForCheapTrick(fs:     c_int,
-                                   option: *mut CheapTrickOption);
+                                   option: *const CheapTrickOption) -> c_int;
+    pub fn GetF0FloorForCheapTrick(fs:       c_int,
+                                   fft_size: c_int) -> c_double;
 }
 
 // Codec
 #[link(name = "codec")]
-extern {
+extern "C" {
     pub fn GetNumberOfAperiodicities(fs: c_int) -> c_int;
     pub fn CodeAperiodicity(aperiodicity:       *const *const c_double,
 			    f0_length:    	c_int,
@@ -65,23 +67,29 @@ extern {
 
 // D4C
 #[repr(C)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct D4COption {
     pub threshold: c_double,
 }
 
 impl D4COption {
     pub fn new() -> Self {
+        let mut option = std::mem::MaybeUninit::<D4COption>::uninit();
         unsafe {
-            let mut option:D4COption = std::mem::MaybeUninit::uninit().assume_init();
-            InitializeD4COption(&mut option as *mut _);
-            option
+            InitializeD4COption(option.as_mut_ptr());
+            option.assume_init()
         }
     }
 }
 
+impl Default for D4COption {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[link(name = "d4c")]
-extern {
+extern "C" {
     pub fn InitializeD4COption(option: *mut D4COption);
     pub fn D4C(x:                  *const c_double,
 	       x_length:           c_int,
@@ -96,7 +104,7 @@ extern {
 
 // Dio
 #[repr(C)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DioOption {
     pub f0_floor:           c_double,
     pub f0_ceil:            c_double,
@@ -108,16 +116,22 @@ pub struct DioOption {
 
 impl DioOption {
     pub fn new() -> Self {
+        let mut option = std::mem::MaybeUninit::<DioOption>::uninit();
         unsafe {
-            let mut option:DioOption = std::mem::MaybeUninit::uninit().assume_init();
-            InitializeDioOption(&mut option as *mut _);
-            option
+            InitializeDioOption(option.as_mut_ptr());
+            option.assume_init()
         }
     }
 }
 
+impl Default for DioOption {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[link(name = "dio")]
-extern {
+extern "C" {
     pub fn Dio(x:                  *const c_double,
                x_length:           c_int,
                fs:                 c_int,
@@ -132,7 +146,7 @@ extern {
 
 // Harvest
 #[repr(C)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct HarvestOption {
     pub f0_floor:     c_double,
     pub f0_ceil:      c_double,
@@ -141,16 +155,22 @@ pub struct HarvestOption {
 
 impl HarvestOption {
     pub fn new() -> Self {
+        let mut option = std::mem::MaybeUninit::<HarvestOption>::uninit();
         unsafe {
-            let mut option:HarvestOption = std::mem::MaybeUninit::uninit().assume_init();
-            InitializeHarvestOption(&mut option as *mut _);
-            option
+            InitializeHarvestOption(option.as_mut_ptr());
+            option.assume_init()
         }
     }
 }
 
+impl Default for HarvestOption {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[link(name = "harvest")]
-extern {
+extern "C" {
     pub fn Harvest(x:                  *const c_double,
 		   x_length:           c_int,
 		   fs:                 c_int,
@@ -165,7 +185,7 @@ extern {
 
 // StoneMask
 #[link(name = "stonemask")]
-extern {
+extern "C" {
     pub fn StoneMask(x:                  *const c_double,
 		     x_length:           c_int,
 		     fs:                 c_int,
@@ -177,7 +197,7 @@ extern {
 
 // Synthesis
 #[link(name = "synthesis")]
-extern {
+extern "C" {
     pub fn Synthesis(f0:           *const c_double,
 		     f0_length:    c_int,
 		     spectrogram:  *const *const c_double,
@@ -189,7 +209,121 @@ extern {
 		     y:            *mut c_double);
 }
 
+// FFT structures embedded in WorldSynthesizer
+#[allow(non_camel_case_types)]
+pub type fft_complex = [c_double; 2];
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct fft_plan {
+    pub n:     c_int,
+    pub sign:  c_int,
+    pub flags: c_uint,
+    pub c_in:  *mut fft_complex,
+    pub in_:   *mut c_double,
+    pub c_out: *mut fft_complex,
+    pub out:   *mut c_double,
+    pub input: *mut c_double,
+    pub ip:    *mut c_int,
+    pub w:     *mut c_double,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ForwardRealFFT {
+    pub fft_size:    c_int,
+    pub waveform:    *mut c_double,
+    pub spectrum:    *mut fft_complex,
+    pub forward_fft: fft_plan,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct InverseRealFFT {
+    pub fft_size:    c_int,
+    pub waveform:    *mut c_double,
+    pub spectrum:    *mut fft_complex,
+    pub inverse_fft: fft_plan,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct MinimumPhaseAnalysis {
+    pub fft_size:               c_int,
+    pub log_spectrum:           *mut c_double,
+    pub minimum_phase_spectrum: *mut fft_complex,
+    pub cepstrum:               *mut fft_complex,
+    pub inverse_fft:            fft_plan,
+    pub forward_fft:            fft_plan,
+}
+
+// SynthesisRealtime
+#[repr(C)]
+#[derive(Debug)]
+pub struct WorldSynthesizer {
+    pub fs:                    c_int,
+    pub frame_period:          c_double,
+    pub buffer_size:           c_int,
+    pub number_of_pointers:    c_int,
+    pub fft_size:              c_int,
+
+    pub buffer:                *mut c_double,
+    pub current_pointer:       c_int,
+    pub i:                     c_int,
+
+    pub dc_remover:            *mut c_double,
+
+    pub f0_length:             *mut c_int,
+    pub f0_origin:             *mut c_int,
+    pub spectrogram:           *mut *mut *mut c_double,
+    pub aperiodicity:          *mut *mut *mut c_double,
+
+    pub current_pointer2:      c_int,
+    pub head_pointer:          c_int,
+    pub synthesized_sample:    c_int,
+
+    pub handoff:               c_int,
+    pub handoff_phase:         c_double,
+    pub handoff_f0:            c_double,
+    pub last_location:         c_int,
+
+    pub cumulative_frame:      c_int,
+    pub current_frame:         c_int,
+
+    pub interpolated_vuv:      *mut *mut c_double,
+    pub pulse_locations:       *mut *mut c_double,
+    pub pulse_locations_index: *mut *mut c_int,
+    pub number_of_pulses:      *mut c_int,
+
+    pub impulse_response:      *mut c_double,
+
+    pub minimum_phase:         MinimumPhaseAnalysis,
+    pub inverse_real_fft:      InverseRealFFT,
+    pub forward_real_fft:      ForwardRealFFT,
+}
+
+#[link(name = "synthesisrealtime")]
+extern "C" {
+    pub fn InitializeSynthesizer(fs:                 c_int,
+				 frame_period:       c_double,
+				 fft_size:           c_int,
+				 buffer_size:        c_int,
+				 number_of_pointers: c_int,
+				 synth:              *mut WorldSynthesizer);
+    pub fn AddParameters(f0:           *mut c_double,
+			 f0_length:    c_int,
+			 spectrogram:  *mut *mut c_double,
+			 aperiodicity: *mut *mut c_double,
+			 synth:        *mut WorldSynthesizer) -> c_int;
+    pub fn RefreshSynthesizer(synth: *mut WorldSynthesizer);
+    pub fn DestroySynthesizer(synth: *mut WorldSynthesizer);
+    pub fn IsLocked(synth: *mut WorldSynthesizer) -> c_int;
+    pub fn Synthesis2(synth: *mut WorldSynthesizer) -> c_int;
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_vec, clippy::unnecessary_cast)]
 mod tests {
     #[allow(dead_code)]
     fn get_type<T>(_: T) -> &'static str {
@@ -197,7 +331,7 @@ mod tests {
     }
 
     // CheapTrick test
-    use crate::{CheapTrick, GetFFTSizeForCheapTrick, CheapTrickOption};
+    use crate::{CheapTrick, GetFFTSizeForCheapTrick, GetF0FloorForCheapTrick, CheapTrickOption};
 
     #[test]
     fn test_initialize_cheaptrick_option() {
@@ -211,8 +345,19 @@ mod tests {
         let fs = 44100;
         let mut option = CheapTrickOption::new(fs);
         unsafe {
-            GetFFTSizeForCheapTrick(fs, &mut option as *mut _);
-            assert_eq!(option.fft_size, 2048);
+            assert_eq!(GetFFTSizeForCheapTrick(fs, &option as *const _), 2048);
+        }
+        option.f0_floor = 40.0;
+        unsafe {
+            assert_eq!(GetFFTSizeForCheapTrick(fs, &option as *const _), 4096);
+        }
+    }
+
+    #[test]
+    fn test_get_f0_floor_for_cheaptrick() {
+        let fs = 44100;
+        unsafe {
+            assert_eq!(GetF0FloorForCheapTrick(fs, 2048), 3.0 * 44100.0 / 2045.0);
         }
     }
 
@@ -226,7 +371,7 @@ mod tests {
         let f0_length          = f0.len() as i32;
         let mut option         = CheapTrickOption::new(fs);
         unsafe {
-            GetFFTSizeForCheapTrick(fs, &mut option as *mut _);
+            option.fft_size = GetFFTSizeForCheapTrick(fs, &option as *const _);
         }
 	let xl = (option.fft_size/2+1) as usize;
 	let yl = f0_length as usize;
@@ -313,7 +458,7 @@ mod tests {
         let f0_length          = f0.len() as i32;
         let mut option         = CheapTrickOption::new(fs);
         unsafe {
-            GetFFTSizeForCheapTrick(fs, &mut option as *mut _);
+            option.fft_size = GetFFTSizeForCheapTrick(fs, &option as *const _);
         }
 	let number_of_dimensions = 256_i32;
 	let xl = (option.fft_size/2+1) as usize;
@@ -348,7 +493,7 @@ mod tests {
         let f0_length          = f0.len() as i32;
         let mut option         = CheapTrickOption::new(fs);
         unsafe {
-            GetFFTSizeForCheapTrick(fs, &mut option as *mut _);
+            option.fft_size = GetFFTSizeForCheapTrick(fs, &option as *const _);
         }
 	let number_of_dimensions = 256_i32;
 	let xl = (option.fft_size/2+1) as usize;
@@ -535,7 +680,7 @@ mod tests {
 
         let mut option         = CheapTrickOption::new(fs);
         unsafe {
-            GetFFTSizeForCheapTrick(fs, &mut option as *mut _);
+            option.fft_size = GetFFTSizeForCheapTrick(fs, &option as *const _);
         }
 	let xl = (option.fft_size/2+1) as usize;
 	let yl = f0_length as usize;
@@ -565,4 +710,46 @@ mod tests {
 	}
 	assert_eq!(y.len(), y_length as usize);
     }
+
+    // SynthesisRealtime test
+    use crate::{
+	WorldSynthesizer,
+	InitializeSynthesizer,
+	AddParameters,
+	RefreshSynthesizer,
+	DestroySynthesizer,
+	IsLocked,
+	Synthesis2
+    };
+
+    #[test]
+    fn test_synthesis_realtime() {
+	let fs                 = 16000;
+	let frame_period       = 5.0;
+	let fft_size           = 1024;
+	let buffer_size        = 64;
+	let number_of_pointers = 100;
+	let f0_length          = 20;
+	let mut f0             = vec![100.0; f0_length];
+	let mut spectrogram    = vec![vec![1e-4; fft_size/2+1]; f0_length];
+	let mut aperiodicity   = vec![vec![0.1; fft_size/2+1]; f0_length];
+	let mut spectrogram_ptr  = spectrogram.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
+	let mut aperiodicity_ptr = aperiodicity.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
+	let mut synth: WorldSynthesizer = unsafe { std::mem::MaybeUninit::zeroed().assume_init() };
+	let mut y = Vec::new();
+	unsafe {
+	    InitializeSynthesizer(fs, frame_period, fft_size as i32, buffer_size, number_of_pointers, &mut synth as *mut _);
+	    assert_eq!(synth.buffer_size, buffer_size);
+	    assert_eq!(AddParameters(f0.as_mut_ptr(), f0_length as i32, spectrogram_ptr.as_mut_ptr(), aperiodicity_ptr.as_mut_ptr(), &mut synth as *mut _), 1);
+	    while Synthesis2(&mut synth as *mut _) != 0 {
+		y.extend_from_slice(std::slice::from_raw_parts(synth.buffer, buffer_size as usize));
+	    }
+	    assert_eq!(IsLocked(&mut synth as *mut _), 0);
+	    RefreshSynthesizer(&mut synth as *mut _);
+	    assert_eq!(synth.head_pointer, 0);
+	    DestroySynthesizer(&mut synth as *mut _);
+	}
+	assert!(!y.is_empty());
+	assert_eq!(y.len() % buffer_size as usize, 0);
+    }
 }
@@ -0,0 +1,167 @@
+use crate::{WorldParameters, CheapTrickOption, D4COption, DioOption, HarvestOption, cheaptrick, d4c, dio, harvest, stonemask};
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum F0Method {
+    Dio,
+    Harvest,
+}
+
+/// Runs F0 estimation, CheapTrick and D4C with one consistent set of settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analyzer {
+    f0_method:    F0Method,
+    stonemask:    bool,
+    frame_period: f64,
+    f0_floor:     f64,
+    f0_ceil:      f64,
+    threshold:    f64,
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        let option = HarvestOption::new();
+        Analyzer {
+            f0_method:    F0Method::Harvest,
+            stonemask:    false,
+            frame_period: option.frame_period,
+            f0_floor:     option.f0_floor,
+            f0_ceil:      option.f0_ceil,
+            threshold:    D4COption::new().threshold,
+        }
+    }
+}
+
+impl Analyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn f0_method(mut self, f0_method: F0Method) -> Self {
+        self.f0_method = f0_method;
+        self
+    }
+
+    pub fn stonemask(mut self, stonemask: bool) -> Self {
+        self.stonemask = stonemask;
+        self
+    }
+
+    pub fn frame_period(mut self, frame_period: f64) -> Self {
+        self.frame_period = frame_period;
+        self
+    }
+
+    pub fn f0_floor(mut self, f0_floor: f64) -> Self {
+        self.f0_floor = f0_floor;
+        self
+    }
+
+    pub fn f0_ceil(mut self, f0_ceil: f64) -> Self {
+        self.f0_ceil = f0_ceil;
+        self
+    }
+
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn validate(&self, fs: i32) -> Result<()> {
+        match self.f0_method {
+            F0Method::Dio     => DioOption::new().f0_floor(self.f0_floor).f0_ceil(self.f0_ceil).frame_period(self.frame_period).validate(fs)?,
+            F0Method::Harvest => HarvestOption::new().f0_floor(self.f0_floor).f0_ceil(self.f0_ceil).frame_period(self.frame_period).validate(fs)?,
+        }
+        self.cheaptrick_option().validate(fs)?;
+        self.d4c_option().validate()
+    }
+
+    pub(crate) fn period(&self) -> f64 {
+        self.frame_period
+    }
+
+    pub(crate) fn cheaptrick_option(&self) -> CheapTrickOption {
+        CheapTrickOption::new().f0_floor(self.f0_floor)
+    }
+
+    pub(crate) fn d4c_option(&self) -> D4COption {
+        D4COption::new().threshold(self.threshold)
+    }
+
+    pub fn estimate_f0(&self, x: &[f64], fs: i32) -> Result<(Vec<f64>, Vec<f64>)> {
+        let (temporal_positions, f0) = match self.f0_method {
+            F0Method::Dio => {
+                let option = DioOption::new().f0_floor(self.f0_floor).f0_ceil(self.f0_ceil).frame_period(self.frame_period);
+                dio(x, fs, &option)?
+            },
+            F0Method::Harvest => {
+                let option = HarvestOption::new().f0_floor(self.f0_floor).f0_ceil(self.f0_ceil).frame_period(self.frame_period);
+                harvest(x, fs, &option)?
+            },
+        };
+        let f0 = if self.stonemask {
+            stonemask(x, fs, &temporal_positions, &f0)?
+        } else {
+            f0
+        };
+        Ok((temporal_positions, f0))
+    }
+
+    pub fn analyze(&self, x: &[f64], fs: i32) -> Result<WorldParameters> {
+        let (temporal_positions, f0) = self.estimate_f0(x, fs)?;
+        let spectrogram              = cheaptrick(x, fs, &temporal_positions, &f0, &self.cheaptrick_option())?;
+        let fft_size                 = spectrogram.fft_size() as i32;
+        let aperiodicity             = d4c(x, fs, &temporal_positions, &f0, fft_size, &self.d4c_option())?;
+        let parameters               = WorldParameters::new(f0, temporal_positions, spectrogram, aperiodicity, fs, self.frame_period)?;
+        Ok(parameters.with_signal_length(x.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Analyzer, F0Method};
+    use crate::test_signals::harmonics;
+
+    #[test]
+    fn test_analyze_harvest() {
+        let fs         = 16000;
+        let x          = harmonics(fs, 200.0, 0.5);
+        let parameters = Analyzer::new().analyze(&x, fs).unwrap();
+        assert_eq!(parameters.fs(),           fs);
+        assert_eq!(parameters.frame_period(), 5.0);
+        assert_eq!(parameters.frames(),       parameters.spectrogram().frames());
+        let middle = parameters.f0()[parameters.frames()/2];
+        assert!((middle - 200.0).abs() < 5.0, "f0 = {}", middle);
+    }
+
+    #[test]
+    fn test_analyze_dio_stonemask() {
+        let fs         = 16000;
+        let x          = harmonics(fs, 200.0, 0.5);
+        let parameters = Analyzer::new().f0_method(F0Method::Dio).stonemask(true).frame_period(10.0).analyze(&x, fs).unwrap();
+        assert_eq!(parameters.synthesize().unwrap().len(), x.len());
+        assert_eq!(parameters.frame_period(), 10.0);
+        assert_eq!(parameters.temporal_positions()[1], 0.01);
+        let middle = parameters.f0()[parameters.frames()/2];
+        assert!((middle - 200.0).abs() < 5.0, "f0 = {}", middle);
+    }
+
+    #[test]
+    fn test_analyze_low_f0_floor() {
+        let fs         = 16000;
+        let x          = harmonics(fs, 200.0, 0.5);
+        let parameters = Analyzer::new().f0_floor(40.0).analyze(&x, fs).unwrap();
+        assert_eq!(parameters.fft_size(), 2048);
+        assert_eq!(parameters.aperiodicity().fft_size(), 2048);
+    }
+
+    #[test]
+    fn test_synthesize() {
+        let fs         = 16000;
+        let x          = harmonics(fs, 200.0, 0.5);
+        let parameters = Analyzer::new().analyze(&x, fs).unwrap();
+        let y          = parameters.synthesize().unwrap();
+        assert_eq!(y.len(), x.len());
+        assert!(y.iter().all(|v| v.is_finite()));
+    }
+}
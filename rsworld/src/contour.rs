@@ -0,0 +1,126 @@
+use crate::error::{Result, check_same_length, check_finite};
+
+/// How `continuous_log_f0` fills unvoiced gaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Straight lines in log-F0 between the voiced frames around each gap.
+    #[default]
+    Linear,
+    /// Natural cubic spline through all voiced frames.
+    Spline,
+}
+
+// Second derivatives of the natural cubic spline through (x, y), by the
+// tridiagonal (Thomas) solve with zero curvature at both ends.
+fn spline_curvature(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n     = x.len();
+    let mut m = vec![0.0; n];
+    if n < 3 {
+        return m;
+    }
+    let mut diagonal = vec![0.0; n];
+    let mut rhs      = vec![0.0; n];
+    for i in 1..n-1 {
+        let (h0, h1) = (x[i] - x[i-1], x[i+1] - x[i]);
+        diagonal[i]  = 2.0 * (h0 + h1);
+        rhs[i]       = 6.0 * ((y[i+1] - y[i]) / h1 - (y[i] - y[i-1]) / h0);
+        if i > 1 {
+            let factor   = h0 / diagonal[i-1];
+            diagonal[i] -= factor * h0;
+            rhs[i]      -= factor * rhs[i-1];
+        }
+    }
+    for i in (1..n-1).rev() {
+        let upper = if i < n-2 { (x[i+1] - x[i]) * m[i+1] } else { 0.0 };
+        m[i] = (rhs[i] - upper) / diagonal[i];
+    }
+    m
+}
+
+/// Log-F0 with every unvoiced frame filled in, plus the voicing mask to undo it.
+///
+/// Gaps between voiced frames are interpolated as `interpolation` says; frames
+/// before the first and after the last voiced frame hold the nearest voiced
+/// value. A contour with no voiced frame at all gives 0.0 throughout and an
+/// all-false mask.
+pub fn continuous_log_f0(f0: &[f64], interpolation: Interpolation) -> Result<(Vec<f64>, Vec<bool>)> {
+    check_finite("f0", f0)?;
+    let vuv: Vec<bool> = f0.iter().map(|&v| v > 0.0).collect();
+    let x: Vec<f64>    = (0..f0.len()).filter(|&i| vuv[i]).map(|i| i as f64).collect();
+    let y: Vec<f64>    = f0.iter().filter(|&&v| v > 0.0).map(|v| v.ln()).collect();
+    if x.is_empty() {
+        return Ok((vec![0.0; f0.len()], vuv));
+    }
+    let curvature = match interpolation {
+        Interpolation::Linear => vec![0.0; x.len()],
+        Interpolation::Spline => spline_curvature(&x, &y),
+    };
+    let mut log_f0 = Vec::with_capacity(f0.len());
+    let mut knot   = 0;
+    for frame in 0..f0.len() {
+        let t = frame as f64;
+        while knot+1 < x.len() && x[knot+1] <= t {
+            knot += 1;
+        }
+        log_f0.push(if t <= x[0] {
+            y[0]
+        } else if knot+1 == x.len() {
+            y[knot]
+        } else {
+            let h      = x[knot+1] - x[knot];
+            let (a, b) = ((x[knot+1] - t) / h, (t - x[knot]) / h);
+            a * y[knot] + b * y[knot+1]
+                + ((a*a*a - a) * curvature[knot] + (b*b*b - b) * curvature[knot+1]) * h*h / 6.0
+        });
+    }
+    Ok((log_f0, vuv))
+}
+
+/// Inverse of `continuous_log_f0`: F0 in Hz where `vuv` is set and 0.0 elsewhere,
+/// as `synthesis` expects.
+pub fn discontinuous_f0(log_f0: &[f64], vuv: &[bool]) -> Result<Vec<f64>> {
+    check_same_length("vuv", log_f0.len(), vuv.len())?;
+    check_finite("log_f0", log_f0)?;
+    Ok(log_f0.iter().zip(vuv).map(|(&v, &voiced)| if voiced { v.exp() } else { 0.0 }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{WorldError, Interpolation, continuous_log_f0, discontinuous_f0};
+
+    #[test]
+    fn test_linear() {
+        let f0 = [0.0, 100.0, 0.0, 0.0, 800.0, 0.0];
+        let (log_f0, vuv) = continuous_log_f0(&f0, Interpolation::Linear).unwrap();
+        assert_eq!(vuv, vec![false, true, false, false, true, false]);
+        // Edges hold the nearest voiced value; the gap is a straight line in log-F0, so 200, 400 Hz.
+        let expected = [100.0, 100.0, 200.0, 400.0, 800.0, 800.0];
+        for (v, e) in log_f0.iter().zip(&expected) {
+            assert!((v.exp() - e).abs() < 1e-9);
+        }
+        assert_eq!(discontinuous_f0(&log_f0, &vuv).unwrap().iter().map(|v| v.round()).collect::<Vec<_>>(), f0.to_vec());
+    }
+
+    #[test]
+    fn test_spline() {
+        let f0: Vec<f64> = (0..40).map(|i| if i % 7 == 3 { 0.0 } else { 150.0 + 30.0 * (i as f64 / 6.0).sin() }).collect();
+        let (spline, vuv) = continuous_log_f0(&f0, Interpolation::Spline).unwrap();
+        let (linear, _)   = continuous_log_f0(&f0, Interpolation::Linear).unwrap();
+        for (i, &v) in f0.iter().enumerate() {
+            if vuv[i] {
+                assert!((spline[i] - v.ln()).abs() < 1e-12);
+            } else {
+                // On a smooth contour the spline tracks the underlying curve more closely.
+                let truth = (150.0 + 30.0 * (i as f64 / 6.0).sin()).ln();
+                assert!((spline[i] - truth).abs() <= (linear[i] - truth).abs() + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unvoiced() {
+        assert_eq!(continuous_log_f0(&[0.0, 0.0], Interpolation::Spline), Ok((vec![0.0, 0.0], vec![false, false])));
+        assert_eq!(discontinuous_f0(&[0.0, 0.0], &[true]),
+                   Err(WorldError::LengthMismatch { name: "vuv", expected: 2, actual: 1 }));
+    }
+}
@@ -0,0 +1,248 @@
+use std::slice::ChunksExact;
+
+use crate::{WorldParameters, MelCepstrum, CodedSpectrogram, MelSpectrogram, Mfcc};
+use crate::manipulation::resample_frames;
+use crate::error::{WorldError, Result, check_same_length};
+
+/// Frame-wise features `Dtw::align` can compare.
+pub trait Features {
+    fn rows(&self) -> ChunksExact<'_, f64>;
+}
+
+macro_rules! features {
+    ($($name:ident),*) => {
+        $(
+            impl Features for $name {
+                fn rows(&self) -> ChunksExact<'_, f64> {
+                    $name::rows(self)
+                }
+            }
+        )*
+    };
+}
+
+features!(MelCepstrum, CodedSpectrogram, MelSpectrogram, Mfcc);
+
+/// Local moves allowed between cells of the cost matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StepPattern {
+    /// Horizontal, vertical and diagonal moves, all weighted 1. The cost is not normalised.
+    Symmetric1,
+    /// As `Symmetric1` with the diagonal weighted 2; the cost is divided by the sum of lengths.
+    #[default]
+    Symmetric2,
+    /// Every source frame is used exactly once and the target may advance 0, 1 or 2
+    /// frames per step; the cost is divided by the source length.
+    Asymmetric,
+}
+
+impl StepPattern {
+    // (source step, target step, weight)
+    fn steps(self) -> &'static [(usize, usize, f64)] {
+        match self {
+            StepPattern::Symmetric1 => &[(1, 1, 1.0), (1, 0, 1.0), (0, 1, 1.0)],
+            StepPattern::Symmetric2 => &[(1, 1, 2.0), (1, 0, 1.0), (0, 1, 1.0)],
+            StepPattern::Asymmetric => &[(1, 1, 1.0), (1, 0, 1.0), (1, 2, 1.0)],
+        }
+    }
+
+    fn normalise(self, cost: f64, source: usize, target: usize) -> f64 {
+        match self {
+            StepPattern::Symmetric1 => cost,
+            StepPattern::Symmetric2 => cost / (source + target) as f64,
+            StepPattern::Asymmetric => cost / source as f64,
+        }
+    }
+}
+
+/// Global constraint on how far the path may leave the diagonal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Band {
+    #[default]
+    None,
+    /// Target frames at most this far from the line joining both corners.
+    SakoeChiba(usize),
+}
+
+/// The result of `Dtw::align`: matched `(source, target)` frame pairs from
+/// `(0, 0)` to the last frame of both, and the accumulated distance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarpingPath {
+    pub path:     Vec<(usize, usize)>,
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dtw {
+    step_pattern: StepPattern,
+    band:         Band,
+}
+
+impl Dtw {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn step_pattern(mut self, step_pattern: StepPattern) -> Self {
+        self.step_pattern = step_pattern;
+        self
+    }
+
+    pub fn band(mut self, band: Band) -> Self {
+        self.band = band;
+        self
+    }
+
+    fn in_band(&self, i: usize, j: usize, source: usize, target: usize) -> bool {
+        match self.band {
+            Band::None => true,
+            Band::SakoeChiba(radius) => {
+                let centre = if source > 1 { i as f64 * (target-1) as f64 / (source-1) as f64 } else { 0.0 };
+                (j as f64 - centre).abs() <= radius as f64
+            },
+        }
+    }
+
+    /// Aligns two feature sequences of the same dimension by Euclidean frame distance.
+    pub fn align<T: Features>(&self, source: &T, target: &T) -> Result<WarpingPath> {
+        let source: Vec<&[f64]> = source.rows().collect();
+        let target: Vec<&[f64]> = target.rows().collect();
+        if source.is_empty() || target.is_empty() {
+            return Err(WorldError::EmptySpectrogram);
+        }
+        check_same_length("target dimensions", source[0].len(), target[0].len())?;
+        let (n, m)   = (source.len(), target.len());
+        let steps    = self.step_pattern.steps();
+        let mut cost = vec![f64::INFINITY; n*m];
+        let mut from = vec![0; n*m];
+        for i in 0..n {
+            for j in 0..m {
+                if !self.in_band(i, j, n, m) {
+                    continue;
+                }
+                let d = source[i].iter().zip(target[j]).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt();
+                if i == 0 && j == 0 {
+                    cost[0] = d;
+                    continue;
+                }
+                for (step, &(di, dj, weight)) in steps.iter().enumerate() {
+                    if di > i || dj > j {
+                        continue;
+                    }
+                    let candidate = cost[(i-di)*m+j-dj] + weight * d;
+                    if candidate < cost[i*m+j] {
+                        cost[i*m+j] = candidate;
+                        from[i*m+j] = step;
+                    }
+                }
+            }
+        }
+        if !cost[n*m-1].is_finite() {
+            return Err(WorldError::NoWarpingPath);
+        }
+        let (mut i, mut j) = (n-1, m-1);
+        let mut path       = vec![(i, j)];
+        while (i, j) != (0, 0) {
+            let (di, dj, _) = steps[from[i*m+j]];
+            i -= di;
+            j -= dj;
+            path.push((i, j));
+        }
+        path.reverse();
+        Ok(WarpingPath { path, distance: self.step_pattern.normalise(cost[n*m-1], n, m) })
+    }
+}
+
+/// Moves `source` onto the timeline of the target it was aligned with. Each target
+/// frame takes the source at the mean of the frames matched to it, interpolated as
+/// in `change_frame_period`, so a path from `StepPattern::Asymmetric` or one with
+/// many-to-one matches both work. Every envelope bin must be positive and finite.
+pub fn warp_parameters(source: &WorldParameters, path: &WarpingPath) -> Result<WorldParameters> {
+    let frames = path.path.last().map_or(0, |&(_, j)| j+1);
+    if frames == 0 || source.frames() == 0 {
+        return Err(WorldError::EmptySpectrogram);
+    }
+    let mut sums = vec![(0.0, 0usize); frames];
+    for &(i, j) in &path.path {
+        if i >= source.frames() {
+            return Err(WorldError::LengthMismatch { name: "path", expected: source.frames(), actual: i+1 });
+        }
+        sums[j].0 += i as f64;
+        sums[j].1 += 1;
+    }
+    // Target frames skipped by an asymmetric path sit between their neighbours.
+    let mut positions: Vec<Option<f64>> = sums.iter().map(|&(sum, count)| if count > 0 { Some(sum / count as f64) } else { None }).collect();
+    for j in 1..frames.saturating_sub(1) {
+        if positions[j].is_none() {
+            positions[j] = positions[j-1].zip(positions[j+1]).map(|(a, b)| (a + b) / 2.0);
+        }
+    }
+    let positions: Vec<f64> = positions.into_iter().map(|p| p.unwrap_or(0.0)).collect();
+    resample_frames(source, &positions, source.frame_period())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{WorldParameters, Spectrogram, Aperiodicity, MelCepstrum, WorldError, Dtw, StepPattern, Band, warp_parameters};
+
+    fn features(values: &[f64]) -> MelCepstrum {
+        MelCepstrum::from_vec(values.len(), 1, values.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_identical() {
+        let x    = features(&[0.0, 1.0, 2.0, 3.0]);
+        let warp = Dtw::new().align(&x, &x).unwrap();
+        assert_eq!(warp.path, vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+        assert_eq!(warp.distance, 0.0);
+    }
+
+    #[test]
+    fn test_stretched() {
+        let source = features(&[0.0, 1.0, 2.0, 3.0]);
+        let target = features(&[0.0, 0.0, 1.0, 1.0, 2.0, 3.0, 3.0]);
+        let warp   = Dtw::new().align(&source, &target).unwrap();
+        assert_eq!(warp.path, vec![(0, 0), (0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (3, 6)]);
+        assert_eq!(warp.distance, 0.0);
+
+        let warp = Dtw::new().step_pattern(StepPattern::Asymmetric).align(&target, &source).unwrap();
+        assert_eq!(warp.path.iter().map(|&(i, _)| i).collect::<Vec<_>>(), (0..7).collect::<Vec<_>>());
+        assert_eq!(warp.distance, 0.0);
+    }
+
+    #[test]
+    fn test_band() {
+        let source = features(&[0.0, 5.0, 5.0, 5.0, 5.0, 5.0]);
+        let target = features(&[0.0, 0.0, 0.0, 0.0, 0.0, 5.0]);
+        let free   = Dtw::new().align(&source, &target).unwrap();
+        let banded = Dtw::new().band(Band::SakoeChiba(1)).align(&source, &target).unwrap();
+        assert_eq!(free.distance, 0.0);
+        assert!(banded.distance > 0.0);
+        assert!(banded.path.iter().all(|&(i, j)| (i as i64 - j as i64).abs() <= 1));
+
+        let long = features(&[0.0; 12]);
+        assert_eq!(Dtw::new().step_pattern(StepPattern::Asymmetric).align(&source, &long).map(|w| w.distance),
+                   Err(WorldError::NoWarpingPath));
+    }
+
+    #[test]
+    fn test_warp_parameters() {
+        let spectrogram  = Spectrogram::from_vec(3, 2, vec![1.0, 1.0, 2.0, 2.0, 4.0, 4.0]).unwrap();
+        let aperiodicity = Aperiodicity::from_vec(3, 2, vec![0.0; 6]).unwrap();
+        let source = WorldParameters::new(vec![100.0, 0.0, 200.0], vec![0.0, 0.005, 0.01], spectrogram, aperiodicity, 16000, 5.0).unwrap();
+        let warp   = Dtw::new().align(&features(&[0.0, 1.0, 2.0]), &features(&[0.0, 0.0, 1.0, 2.0, 2.0])).unwrap();
+        let warped = warp_parameters(&source, &warp).unwrap();
+        assert_eq!(warped.frames(), 5);
+        assert_eq!(warped.f0(), &[100.0, 100.0, 0.0, 200.0, 200.0]);
+        assert_eq!(warped.spectrogram().row(2), &[2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_warp_parameters_zero_bin() {
+        let spectrogram  = Spectrogram::from_vec(3, 2, vec![1.0, 1.0, 2.0, 0.0, 4.0, 4.0]).unwrap();
+        let aperiodicity = Aperiodicity::from_vec(3, 2, vec![0.0; 6]).unwrap();
+        let source = WorldParameters::new(vec![100.0, 0.0, 200.0], vec![0.0, 0.005, 0.01], spectrogram, aperiodicity, 16000, 5.0).unwrap();
+        let warp   = Dtw::new().align(&features(&[0.0, 1.0, 2.0]), &features(&[0.0, 1.0, 2.0])).unwrap();
+        assert_eq!(warp_parameters(&source, &warp), Err(WorldError::NonPositiveValue { name: "spectrogram", index: 3 }));
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldError {
+    EmptySignal,
+    SignalTooShort     { minimum: usize, actual: usize },
+    NonFiniteSample    { name: &'static str, index: usize },
+    NonPositiveValue   { name: &'static str, index: usize },
+    InvalidSampleRate(i32),
+    InvalidFramePeriod(f64),
+    InvalidFftSize(i32),
+    InvalidDimensions(i32),
+    InvalidOption      { name: &'static str, value: f64 },
+    LengthOverflow     { name: &'static str, length: usize },
+    LengthMismatch     { name: &'static str, expected: usize, actual: usize },
+    EmptySpectrogram,
+    TooFewFrames       { minimum: usize, actual: usize },
+    RowLengthMismatch  { name: &'static str, row: usize, expected: usize, actual: usize },
+    InvalidChannel     { channel: usize, channels: usize },
+    NoVoicedFrames,
+    ZeroVariance       { name: &'static str },
+    NoWarpingPath,
+    InvalidFormat(String),
+    Io(String),
+}
+
+impl fmt::Display for WorldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorldError::EmptySignal =>
+                write!(f, "input signal is empty"),
+            WorldError::SignalTooShort { minimum, actual } =>
+                write!(f, "input signal has {} samples, at least {} are required", actual, minimum),
+            WorldError::NonFiniteSample { name, index } =>
+                write!(f, "{}[{}] is NaN or infinite", name, index),
+            WorldError::NonPositiveValue { name, index } =>
+                write!(f, "{}[{}] must be positive", name, index),
+            WorldError::InvalidSampleRate(fs) =>
+                write!(f, "sample rate must be positive, got {}", fs),
+            WorldError::InvalidFramePeriod(frame_period) =>
+                write!(f, "frame period must be positive and finite, got {}", frame_period),
+            WorldError::InvalidFftSize(fft_size) =>
+                write!(f, "fft size must be a positive even number, got {}", fft_size),
+            WorldError::InvalidDimensions(dimensions) =>
+                write!(f, "number of dimensions must be positive, got {}", dimensions),
+            WorldError::InvalidOption { name, value } =>
+                write!(f, "{} is out of range: {}", name, value),
+            WorldError::LengthOverflow { name, length } =>
+                write!(f, "{} has {} elements, which does not fit in an i32", name, length),
+            WorldError::LengthMismatch { name, expected, actual } =>
+                write!(f, "{} has {} elements, expected {}", name, actual, expected),
+            WorldError::EmptySpectrogram =>
+                write!(f, "spectrogram has no frames"),
+            WorldError::TooFewFrames { minimum, actual } =>
+                write!(f, "at least {} frames are required, got {}", minimum, actual),
+            WorldError::RowLengthMismatch { name, row, expected, actual } =>
+                write!(f, "{} row {} has {} bins, expected {}", name, row, actual, expected),
+            WorldError::InvalidChannel { channel, channels } =>
+                write!(f, "channel {} does not exist in a {} channel file", channel, channels),
+            WorldError::NoVoicedFrames =>
+                write!(f, "no frames are voiced in both contours"),
+            WorldError::ZeroVariance { name } =>
+                write!(f, "{} is constant over the compared frames", name),
+            WorldError::NoWarpingPath =>
+                write!(f, "no warping path satisfies the step pattern and band"),
+            WorldError::InvalidFormat(message) =>
+                write!(f, "invalid file format: {}", message),
+            WorldError::Io(message) =>
+                write!(f, "i/o error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for WorldError {}
+
+impl From<std::io::Error> for WorldError {
+    fn from(error: std::io::Error) -> Self {
+        WorldError::Io(error.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, WorldError>;
+
+pub(crate) fn check_fs(fs: i32) -> Result<()> {
+    if fs <= 0 {
+        return Err(WorldError::InvalidSampleRate(fs));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_frame_period(frame_period: f64) -> Result<()> {
+    if !(frame_period.is_finite() && frame_period > 0.0) {
+        return Err(WorldError::InvalidFramePeriod(frame_period));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_fft_size(fft_size: i32) -> Result<()> {
+    if fft_size <= 0 || fft_size % 2 != 0 {
+        return Err(WorldError::InvalidFftSize(fft_size));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_length(name: &'static str, length: usize) -> Result<i32> {
+    if length > i32::MAX as usize {
+        return Err(WorldError::LengthOverflow { name, length });
+    }
+    Ok(length as i32)
+}
+
+pub(crate) fn check_same_length(name: &'static str, expected: usize, actual: usize) -> Result<()> {
+    if expected != actual {
+        return Err(WorldError::LengthMismatch { name, expected, actual });
+    }
+    Ok(())
+}
+
+pub(crate) fn check_finite(name: &'static str, values: &[f64]) -> Result<()> {
+    match values.iter().position(|v| !v.is_finite()) {
+        Some(index) => Err(WorldError::NonFiniteSample { name, index }),
+        None        => Ok(()),
+    }
+}
+
+// Values that are about to go through a logarithm.
+pub(crate) fn check_positive(name: &'static str, values: &[f64]) -> Result<()> {
+    check_finite(name, values)?;
+    match values.iter().position(|&v| v <= 0.0) {
+        Some(index) => Err(WorldError::NonPositiveValue { name, index }),
+        None        => Ok(()),
+    }
+}
+
+pub(crate) fn check_signal(x: &[f64]) -> Result<i32> {
+    if x.is_empty() {
+        return Err(WorldError::EmptySignal);
+    }
+    check_finite("x", x)?;
+    check_length("x", x.len())
+}
+
+// WORLD's decimate reflects the 9 samples next to each end of the signal, so
+// decimating needs at least 10.
+pub(crate) fn check_decimation(x: &[f64], ratio: i32) -> Result<()> {
+    const MINIMUM: usize = 10;
+    if ratio > 1 && x.len() < MINIMUM {
+        return Err(WorldError::SignalTooShort { minimum: MINIMUM, actual: x.len() });
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::error::{WorldError, Result, check_same_length, check_finite};
+
+// Frames on each side used as the reference for octave-jump detection.
+const OCTAVE_RADIUS: usize = 5;
+// A frame counts as an octave error when it lies within this many octaves of
+// twice or half the local reference.
+const OCTAVE_TOLERANCE: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Smoothing {
+    None,
+    /// Running median over an odd number of frames.
+    Median(usize),
+    /// Running mean over an odd number of frames.
+    MovingAverage(usize),
+}
+
+/// What `F0Cleanup::apply` changed, as frame indices.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct F0CleanupReport {
+    /// Frames doubled or halved back into line with their neighbours.
+    pub octave_corrected: Vec<usize>,
+    /// Frames made unvoiced because their voiced segment was too short.
+    pub unvoiced:         Vec<usize>,
+    /// Frames whose value the smoothing changed.
+    pub smoothed:         Vec<usize>,
+}
+
+impl F0CleanupReport {
+    pub fn is_unchanged(&self) -> bool {
+        self.octave_corrected.is_empty() && self.unvoiced.is_empty() && self.smoothed.is_empty()
+    }
+}
+
+/// Post-processing for `dio`/`harvest` contours. Every step works inside voiced
+/// segments only, so unvoiced frames are never pulled into the smoothing and
+/// segments never bleed into one another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct F0Cleanup {
+    octave_correction:   bool,
+    smoothing:           Smoothing,
+    min_voiced_duration: f64,
+}
+
+impl Default for F0Cleanup {
+    fn default() -> Self {
+        F0Cleanup {
+            octave_correction:   true,
+            smoothing:           Smoothing::Median(3),
+            min_voiced_duration: 0.0,
+        }
+    }
+}
+
+fn voiced_segments(f0: &[f64]) -> Vec<(usize, usize)> {
+    let mut segments = Vec::new();
+    let mut start    = None;
+    for (i, &v) in f0.iter().enumerate() {
+        match (v > 0.0, start) {
+            (true, None)     => start = Some(i),
+            (false, Some(s)) => { segments.push((s, i)); start = None; }
+            _                => {}
+        }
+    }
+    if let Some(s) = start {
+        segments.push((s, f0.len()));
+    }
+    segments
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let n = values.len();
+    if n % 2 == 1 { values[n/2] } else { (values[n/2-1] + values[n/2]) / 2.0 }
+}
+
+// Window of `radius` frames on each side of `i`, clipped to the segment.
+fn window(segment: &[f64], i: usize, radius: usize) -> Vec<f64> {
+    segment[i.saturating_sub(radius)..(i+radius+1).min(segment.len())].to_vec()
+}
+
+impl F0Cleanup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn octave_correction(mut self, octave_correction: bool) -> Self {
+        self.octave_correction = octave_correction;
+        self
+    }
+
+    pub fn smoothing(mut self, smoothing: Smoothing) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Voiced segments shorter than this many milliseconds become unvoiced.
+    pub fn min_voiced_duration(mut self, min_voiced_duration: f64) -> Self {
+        self.min_voiced_duration = min_voiced_duration;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Smoothing::Median(length) | Smoothing::MovingAverage(length) = self.smoothing {
+            if length % 2 == 0 {
+                return Err(WorldError::InvalidOption { name: "smoothing", value: length as f64 });
+            }
+        }
+        if !(self.min_voiced_duration.is_finite() && self.min_voiced_duration >= 0.0) {
+            return Err(WorldError::InvalidOption { name: "min_voiced_duration", value: self.min_voiced_duration });
+        }
+        Ok(())
+    }
+
+    /// Cleans `f0` sampled at `temporal_positions` (seconds). Octave jumps are
+    /// corrected first, then short segments removed, then the rest smoothed.
+    pub fn apply(&self, temporal_positions: &[f64], f0: &[f64]) -> Result<(Vec<f64>, F0CleanupReport)> {
+        self.validate()?;
+        check_same_length("f0", temporal_positions.len(), f0.len())?;
+        check_finite("temporal_positions", temporal_positions)?;
+        check_finite("f0", f0)?;
+        let mut f0     = f0.to_vec();
+        let mut report = F0CleanupReport::default();
+
+        if self.octave_correction {
+            for (start, end) in voiced_segments(&f0) {
+                let segment = f0[start..end].to_vec();
+                for i in 0..segment.len() {
+                    let reference = median(&mut window(&segment, i, OCTAVE_RADIUS));
+                    let octaves   = (segment[i] / reference).log2();
+                    let shift     = octaves.round();
+                    if shift != 0.0 && (octaves - shift).abs() < OCTAVE_TOLERANCE {
+                        f0[start+i] = segment[i] / 2f64.powf(shift);
+                        report.octave_corrected.push(start+i);
+                    }
+                }
+            }
+        }
+
+        if self.min_voiced_duration > 0.0 && f0.len() > 1 {
+            let period = (temporal_positions[f0.len()-1] - temporal_positions[0]) / (f0.len()-1) as f64 * 1000.0;
+            for (start, end) in voiced_segments(&f0) {
+                if ((end - start) as f64 * period) < self.min_voiced_duration {
+                    f0[start..end].iter_mut().for_each(|v| *v = 0.0);
+                    report.unvoiced.extend(start..end);
+                }
+            }
+        }
+
+        if let Smoothing::Median(length) | Smoothing::MovingAverage(length) = self.smoothing {
+            for (start, end) in voiced_segments(&f0) {
+                let segment = f0[start..end].to_vec();
+                for i in 0..segment.len() {
+                    // Near the segment edges the window shrinks symmetrically.
+                    let radius   = (length/2).min(i).min(segment.len()-1-i);
+                    let smoothed = match self.smoothing {
+                        Smoothing::Median(_) => median(&mut window(&segment, i, radius)),
+                        Smoothing::MovingAverage(_) => {
+                            let values = window(&segment, i, radius);
+                            values.iter().sum::<f64>() / values.len() as f64
+                        },
+                        Smoothing::None => segment[i],
+                    };
+                    if smoothed != segment[i] {
+                        f0[start+i] = smoothed;
+                        report.smoothed.push(start+i);
+                    }
+                }
+            }
+        }
+        Ok((f0, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{WorldError, F0Cleanup, F0CleanupReport, Smoothing};
+
+    fn positions(frames: usize) -> Vec<f64> {
+        (0..frames).map(|i| i as f64 * 0.005).collect()
+    }
+
+    #[test]
+    fn test_octave_correction() {
+        let f0 = [0.0, 200.0, 202.0, 404.0, 204.0, 103.0, 206.0, 208.0, 0.0];
+        let (cleaned, report) = F0Cleanup::new().smoothing(Smoothing::None).apply(&positions(9), &f0).unwrap();
+        assert_eq!(cleaned, vec![0.0, 200.0, 202.0, 202.0, 204.0, 206.0, 206.0, 208.0, 0.0]);
+        assert_eq!(report.octave_corrected, vec![3, 5]);
+        assert!(report.unvoiced.is_empty() && report.smoothed.is_empty());
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let f0 = [100.0, 100.0, 130.0, 100.0, 0.0, 90.0, 150.0];
+        let (cleaned, report) = F0Cleanup::new().octave_correction(false).apply(&positions(7), &f0).unwrap();
+        // The spike is removed, and the second segment is not mixed with the first.
+        assert_eq!(cleaned, vec![100.0, 100.0, 100.0, 100.0, 0.0, 90.0, 150.0]);
+        assert_eq!(report.smoothed, vec![2]);
+
+        let (cleaned, _) = F0Cleanup::new().octave_correction(false).smoothing(Smoothing::MovingAverage(3))
+                                           .apply(&positions(3), &[100.0, 130.0, 100.0]).unwrap();
+        assert_eq!(cleaned, vec![100.0, 110.0, 100.0]);
+    }
+
+    #[test]
+    fn test_min_voiced_duration() {
+        let f0 = [0.0, 120.0, 0.0, 0.0, 110.0, 110.0, 110.0, 110.0];
+        let (cleaned, report) = F0Cleanup::new().smoothing(Smoothing::None).min_voiced_duration(10.0)
+                                                .apply(&positions(8), &f0).unwrap();
+        assert_eq!(cleaned, vec![0.0, 0.0, 0.0, 0.0, 110.0, 110.0, 110.0, 110.0]);
+        assert_eq!(report, F0CleanupReport { unvoiced: vec![1], ..Default::default() });
+
+        let (_, report) = F0Cleanup::new().apply(&positions(8), &cleaned).unwrap();
+        assert!(report.is_unchanged());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(F0Cleanup::new().smoothing(Smoothing::Median(4)).apply(&[0.0], &[100.0]),
+                   Err(WorldError::InvalidOption { name: "smoothing", value: 4.0 }));
+        assert_eq!(F0Cleanup::new().apply(&[0.0], &[100.0, 100.0]),
+                   Err(WorldError::LengthMismatch { name: "f0", expected: 1, actual: 2 }));
+    }
+}
@@ -0,0 +1,516 @@
+//! Safe wrappers around WORLD's C++ implementation, enabled by the default
+//! `ffi` feature and re-exported at the crate root.
+
+use rsworld_sys::{
+    CheapTrick,
+    GetFFTSizeForCheapTrick,
+    GetNumberOfAperiodicities,
+    CodeAperiodicity,
+    DecodeAperiodicity,
+    CodeSpectralEnvelope,
+    DecodeSpectralEnvelope,
+    D4C,
+    Dio,
+    GetSamplesForDIO,
+    Harvest,
+    GetSamplesForHarvest,
+    StoneMask,
+    Synthesis,
+};
+
+use crate::{
+    Spectrogram,
+    Aperiodicity,
+    CodedSpectrogram,
+    CodedAperiodicity,
+    CheapTrickOption,
+    D4COption,
+    DioOption,
+    HarvestOption,
+    WorldError,
+    check_f0,
+    check_synthesis,
+    check_aperiodicities,
+    get_samples_for_synthesis,
+};
+use crate::error::{
+    Result,
+    check_fs,
+    check_frame_period,
+    check_fft_size,
+    check_length,
+    check_same_length,
+    check_signal,
+    check_decimation,
+};
+
+pub fn get_fft_size_for_cheaptrick(fs: i32, option: &CheapTrickOption) -> Result<i32> {
+    option.validate(fs)?;
+    let option = rsworld_sys::CheapTrickOption { q1: option.q1, f0_floor: option.f0_floor, fft_size: 0 };
+    unsafe {
+	Ok(GetFFTSizeForCheapTrick(fs, &option as *const _))
+    }
+}
+
+pub fn cheaptrick(x: &[f64], fs: i32, temporal_positions: &[f64], f0: &[f64], option: &CheapTrickOption) -> Result<Spectrogram> {
+    let option         = option.to_sys(fs)?;
+    let x_length:  i32 = check_signal(x)?;
+    let f0_length: i32 = check_f0(temporal_positions, f0)?;
+    let mut spectrogram     = Spectrogram::with_fft_size(f0_length as usize, option.fft_size as usize);
+    let mut spectrogram_ptr = spectrogram.row_ptrs_mut();
+    unsafe {
+	CheapTrick(x.as_ptr(), x_length, fs, temporal_positions.as_ptr(), f0.as_ptr(), f0_length, &option as *const _, spectrogram_ptr.as_mut_ptr());
+    }
+    Ok(spectrogram)
+}
+
+pub fn get_number_of_aperiodicities(fs: i32) -> Result<i32> {
+    check_fs(fs)?;
+    unsafe {
+	Ok(GetNumberOfAperiodicities(fs))
+    }
+}
+
+pub fn code_aperiodicity(aperiodicity: &Aperiodicity, fs: i32) -> Result<CodedAperiodicity> {
+    check_fs(fs)?;
+    let f0_length = check_length("aperiodicity", aperiodicity.frames())?;
+    let fft_size  = check_length("aperiodicity", aperiodicity.fft_size())?;
+    check_fft_size(fft_size)?;
+    let n_aperiodicity             = check_aperiodicities(get_number_of_aperiodicities(fs)?)?;
+    let aperiodicity_ptr           = aperiodicity.row_ptrs();
+    let mut coded_aperiodicity     = CodedAperiodicity::zeros(f0_length as usize, n_aperiodicity);
+    let mut coded_aperiodicity_ptr = coded_aperiodicity.row_ptrs_mut();
+    unsafe {
+	CodeAperiodicity(aperiodicity_ptr.as_ptr(), f0_length, fs, fft_size, coded_aperiodicity_ptr.as_mut_ptr());
+    }
+    Ok(coded_aperiodicity)
+}
+
+pub fn decode_aperiodicity(coded_aperiodicity: &CodedAperiodicity, fs: i32, fft_size: i32) -> Result<Aperiodicity> {
+    check_fs(fs)?;
+    check_fft_size(fft_size)?;
+    let f0_length      = check_length("coded_aperiodicity", coded_aperiodicity.frames())?;
+    let n_aperiodicity = check_aperiodicities(get_number_of_aperiodicities(fs)?)?;
+    check_same_length("coded_aperiodicity dimensions", n_aperiodicity, coded_aperiodicity.dimensions())?;
+    let coded_aperiodicity_ptr = coded_aperiodicity.row_ptrs();
+    let mut aperiodicity       = Aperiodicity::with_fft_size(f0_length as usize, fft_size as usize);
+    let mut aperiodicity_ptr   = aperiodicity.row_ptrs_mut();
+    unsafe {
+	DecodeAperiodicity(coded_aperiodicity_ptr.as_ptr(), f0_length, fs, fft_size, aperiodicity_ptr.as_mut_ptr());
+    }
+    Ok(aperiodicity)
+}
+
+pub fn code_spectral_envelope(spectrogram: &Spectrogram, fs: i32, number_of_dimensions: i32) -> Result<CodedSpectrogram> {
+    check_fs(fs)?;
+    if number_of_dimensions <= 0 {
+	return Err(WorldError::InvalidDimensions(number_of_dimensions));
+    }
+    let f0_length = check_length("spectrogram", spectrogram.frames())?;
+    let fft_size  = check_length("spectrogram", spectrogram.fft_size())?;
+    check_fft_size(fft_size)?;
+    let spectrogram_ptr = spectrogram.row_ptrs();
+    let mut coded_spectral_envelope     = CodedSpectrogram::zeros(f0_length as usize, number_of_dimensions as usize);
+    let mut coded_spectral_envelope_ptr = coded_spectral_envelope.row_ptrs_mut();
+    unsafe {
+	CodeSpectralEnvelope(spectrogram_ptr.as_ptr(), f0_length, fs, fft_size, number_of_dimensions, coded_spectral_envelope_ptr.as_mut_ptr());
+
+    }
+    Ok(coded_spectral_envelope)
+}
+
+pub fn decode_spectral_envelope(coded_spectrogram: &CodedSpectrogram, fs: i32, fft_size: i32) -> Result<Spectrogram> {
+    check_fs(fs)?;
+    check_fft_size(fft_size)?;
+    let f0_length            = check_length("coded_spectrogram", coded_spectrogram.frames())?;
+    let number_of_dimensions = check_length("coded_spectrogram", coded_spectrogram.dimensions())?;
+    let mut spectrogram       = Spectrogram::with_fft_size(f0_length as usize, fft_size as usize);
+    let mut spectrogram_ptr   = spectrogram.row_ptrs_mut();
+    let coded_spectrogram_ptr = coded_spectrogram.row_ptrs();
+    unsafe {
+	DecodeSpectralEnvelope(coded_spectrogram_ptr.as_ptr(), f0_length, fs, fft_size, number_of_dimensions, spectrogram_ptr.as_mut_ptr());
+    }
+    Ok(spectrogram)
+}
+
+pub fn d4c(x: &[f64], fs: i32, temporal_positions: &[f64], f0: &[f64], fft_size: i32, option: &D4COption) -> Result<Aperiodicity> {
+    check_fs(fs)?;
+    check_fft_size(fft_size)?;
+    option.validate()?;
+    let option    = option.to_sys();
+    let x_length  = check_signal(x)?;
+    let f0_length = check_f0(temporal_positions, f0)?;
+    let mut aperiodicity     = Aperiodicity::with_fft_size(f0_length as usize, fft_size as usize);
+    let mut aperiodicity_ptr = aperiodicity.row_ptrs_mut();
+    unsafe {
+	D4C(x.as_ptr(), x_length, fs, temporal_positions.as_ptr(), f0.as_ptr(), f0_length, fft_size, &option as *const _, aperiodicity_ptr.as_mut_ptr());
+    }
+    Ok(aperiodicity)
+}
+
+pub fn dio(x: &[f64], fs: i32, option: &DioOption) -> Result<(Vec<f64>, Vec<f64>)> {
+    option.validate(fs)?;
+    let option   = option.to_sys();
+    let x_length = check_signal(x)?;
+    check_decimation(x, option.speed)?;
+    let f0_length: usize;
+    unsafe {
+        f0_length = GetSamplesForDIO(fs, x_length, option.frame_period) as usize;
+    }
+    let mut temporal_positions: Vec<f64> = vec![0.0; f0_length];
+    let mut f0:                 Vec<f64> = vec![0.0; f0_length];
+    unsafe {
+        Dio(x.as_ptr(), x_length, fs, &option as *const _, temporal_positions.as_mut_ptr(), f0.as_mut_ptr());
+    }
+    Ok((temporal_positions, f0))
+}
+
+pub fn harvest(x: &[f64], fs: i32, option: &HarvestOption) -> Result<(Vec<f64>, Vec<f64>)> {
+    option.validate(fs)?;
+    let option   = option.to_sys();
+    let x_length = check_signal(x)?;
+    let f0_length: usize;
+    unsafe {
+        f0_length = GetSamplesForHarvest(fs, x_length, option.frame_period) as usize;
+    }
+    let mut temporal_positions: Vec<f64> = vec![0.0; f0_length];
+    let mut f0:                 Vec<f64> = vec![0.0; f0_length];
+    unsafe {
+        Harvest(x.as_ptr(), x_length, fs, &option as *const _, temporal_positions.as_mut_ptr(), f0.as_mut_ptr());
+    }
+    Ok((temporal_positions, f0))
+}
+
+pub fn stonemask(x: &[f64], fs: i32, temporal_positions: &[f64], f0: &[f64]) -> Result<Vec<f64>> {
+    check_fs(fs)?;
+    let x_length  = check_signal(x)?;
+    let f0_length = check_f0(temporal_positions, f0)?;
+    let mut refined_f0 = vec![0.0; f0_length as usize];
+    unsafe {
+	StoneMask(x.as_ptr(), x_length, fs, temporal_positions.as_ptr(), f0.as_ptr(), f0_length, refined_f0.as_mut_ptr());
+    }
+    Ok(refined_f0)
+}
+
+pub fn synthesis(f0: &[f64], spectrogram: &Spectrogram, aperiodicity: &Aperiodicity, frame_period: f64, fs: i32) -> Result<Vec<f64>> {
+    check_frame_period(frame_period)?;
+    synthesis_with_length(f0, spectrogram, aperiodicity, frame_period, fs, get_samples_for_synthesis(f0.len(), frame_period, fs))
+}
+
+pub fn synthesis_with_length(f0: &[f64], spectrogram: &Spectrogram, aperiodicity: &Aperiodicity, frame_period: f64, fs: i32, y_length: usize) -> Result<Vec<f64>> {
+    let (f0_length, fft_size) = check_synthesis(f0, spectrogram, aperiodicity, frame_period, fs)?;
+    let y_length         = check_length("y", y_length)?;
+    let spectrogram_ptr  = spectrogram.row_ptrs();
+    let aperiodicity_ptr = aperiodicity.row_ptrs();
+    let mut y            = vec![0.0; y_length as usize];
+    if y_length == 0 {
+	return Ok(y);
+    }
+    unsafe {
+	Synthesis(f0.as_ptr(), f0_length, spectrogram_ptr.as_ptr(), aperiodicity_ptr.as_ptr(), fft_size, frame_period, fs, y_length, y.as_mut_ptr())
+    }
+    Ok(y)
+}
+
+#[cfg(test)]
+mod tests {
+    // CheapTrick test
+    use crate::{cheaptrick, get_fft_size_for_cheaptrick, CheapTrickOption};
+
+    #[test]
+    fn test_cheaptrick() {
+	let x                  = vec![0.0; 256];
+	let fs 		       = 44100;
+        let temporal_positions = vec![0.0, 0.005];
+        let f0                 = vec![0.0, 0.0];
+	let option             = CheapTrickOption::new();
+	let spectrogram = cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+	assert_eq!(spectrogram.frames(), f0.len());
+	assert_eq!(spectrogram.bins(),   (option.fft_size(fs).unwrap()/2+1) as usize);
+    }
+
+    // Codec test
+    use crate::{
+	get_number_of_aperiodicities,
+	code_aperiodicity,
+	decode_aperiodicity,
+	code_spectral_envelope,
+	decode_spectral_envelope
+    };
+
+    #[test]
+    fn test_get_number_of_aperiodicities() {
+	let fs = 44100;
+	assert_eq!(get_number_of_aperiodicities(fs).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_code_aperiodicity() {
+	let x                  = vec![0.0; 256];
+	let fs                 = 44100_i32;
+        let temporal_positions = vec![0.0, 0.005];
+        let f0                 = vec![0.0, 0.0];
+	let option             = D4COption::new();
+
+	let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, 2048, &option).unwrap();
+	let coded_aperiodicity = code_aperiodicity(&aperiodicity, fs).unwrap();
+	assert_eq!(coded_aperiodicity.frames(),     f0.len());
+	assert_eq!(coded_aperiodicity.dimensions(), get_number_of_aperiodicities(fs).unwrap() as usize);
+    }
+
+    #[test]
+    fn test_decode_aperiodicity() {
+	let x                  = vec![0.0; 256];
+	let fs                 = 44100_i32;
+        let temporal_positions = vec![0.0, 0.005];
+        let f0                 = vec![0.0, 0.0];
+	let option             = D4COption::new();
+
+	let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, 2048, &option).unwrap();
+	let coded_aperiodicity  = code_aperiodicity(&aperiodicity, fs).unwrap();
+	let decode_aperiodicity = decode_aperiodicity(&coded_aperiodicity, fs, 2048).unwrap();
+	assert_eq!(decode_aperiodicity.frames(), f0.len());
+	assert_eq!(decode_aperiodicity.bins(),   (2048/2+1) as usize);
+	assert_eq!(decode_aperiodicity[(0, 0)],  0.999999999999);
+    }
+
+    #[test]
+    fn test_code_spectral_envelope() {
+	let x                  = vec![0.0; 256];
+	let fs 		       = 44100;
+        let temporal_positions = vec![0.0, 0.005];
+        let f0                 = vec![0.0, 0.0];
+	let option             = CheapTrickOption::new();
+	let spectrogram        = cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+	let number_of_dimensions = 256;
+	let coded_spectrogram  = code_spectral_envelope(&spectrogram, fs, number_of_dimensions).unwrap();
+	assert_eq!(coded_spectrogram.frames(),     f0.len());
+	assert_eq!(coded_spectrogram.dimensions(), number_of_dimensions as usize);
+    }
+
+    #[test]
+    fn test_decode_spectral_envelope() {
+	let x                  = vec![0.0; 256];
+	let fs 		       = 44100;
+        let temporal_positions = vec![0.0, 0.005];
+        let f0                 = vec![0.0, 0.0];
+	let option             = CheapTrickOption::new();
+	let spectrogram        = cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+	let number_of_dimensions = 256;
+	let coded_spectrogram  = code_spectral_envelope(&spectrogram, fs, number_of_dimensions).unwrap();
+	let spectrogram        = decode_spectral_envelope(&coded_spectrogram, fs, option.fft_size(fs).unwrap()).unwrap();
+	assert_eq!(spectrogram.frames(), f0.len());
+	assert_eq!(spectrogram.bins(),   (option.fft_size(fs).unwrap()/2+1) as usize);
+    }
+
+    // D4C test
+    use crate::{d4c, D4COption};
+
+    #[test]
+    fn test_d4c() {
+	let x                  = vec![0.0; 256];
+	let fs                 = 44100_i32;
+        let temporal_positions = vec![0.0, 0.005];
+        let f0                 = vec![0.0, 0.0];
+	let option             = D4COption::new();
+	let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, 2048, &option).unwrap();
+	assert_eq!(aperiodicity.frames(), f0.len());
+	assert_eq!(aperiodicity.bins(),   (2048/2+1) as usize);
+	assert_eq!(aperiodicity[(0, 0)],  0.999999999999);
+    }
+
+    // DIO test
+    use crate::{dio, DioOption};
+
+    #[test]
+    fn test_dio() {
+        let x  = vec![0.0; 256];
+        let fs = 44100;
+        let option = DioOption::new();
+        let (temporal_positions, f0) = dio(&x, fs, &option).unwrap();
+        assert_eq!(temporal_positions, vec![0.0, 0.005]);
+        assert_eq!(f0,                 vec![0.0, 0.0]);
+    }
+
+    // Harvest test
+    use crate::{harvest, HarvestOption};
+
+    #[test]
+    fn test_harvest() {
+        let x  = vec![0.0; 256];
+        let fs = 44100;
+        let option = HarvestOption::new();
+        let (temporal_positions, f0) = harvest(&x, fs, &option).unwrap();
+        assert_eq!(temporal_positions, vec![0.0, 0.005]);
+        assert_eq!(f0,                 vec![0.0, 0.0]);
+    }
+
+    // StoneMask test
+    use crate::stonemask;
+
+    #[test]
+    fn test_stonemask() {
+        let x  = vec![0.0; 256];
+        let fs = 44100;
+        let option = DioOption::new();
+        let (temporal_positions, f0) = dio(&x, fs, &option).unwrap();
+	let refined_f0 = stonemask(&x, fs, &temporal_positions, &f0).unwrap();
+	assert_eq!(refined_f0, vec![0.0, 0.0]);
+    }
+
+    // Synthesis test
+    use crate::{synthesis, synthesis_with_length, get_samples_for_synthesis};
+
+    #[test]
+    fn test_synthesis() {
+        let x      = vec![0.0; 256];
+        let fs     = 44100;
+        for &frame_period in &[5.0, 5.8] {
+            let option = DioOption::new().frame_period(frame_period);
+            let (temporal_positions, f0) = dio(&x, fs, &option).unwrap();
+	    let f0           = stonemask(&x, fs, &temporal_positions, &f0).unwrap();
+	    let option       = CheapTrickOption::new();
+	    let spectrogram  = cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+	    let option       = D4COption::new();
+	    let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, 2048, &option).unwrap();
+	    let y            = synthesis(&f0, &spectrogram, &aperiodicity, frame_period, fs).unwrap();
+	    assert_eq!(y.len(), get_samples_for_synthesis(f0.len(), frame_period, fs));
+        }
+    }
+
+    // Validation test
+    use crate::{WorldError, Spectrogram, Aperiodicity};
+
+    #[test]
+    fn test_empty_signal() {
+        let fs     = 44100;
+        let option = DioOption::new();
+        assert_eq!(dio(&[], fs, &option), Err(WorldError::EmptySignal));
+    }
+
+    #[test]
+    fn test_too_short_to_decimate() {
+        let option = DioOption::new().speed(4);
+        assert_eq!(dio(&[0.1, 0.2, 0.3], 16000, &option), Err(WorldError::SignalTooShort { minimum: 10, actual: 3 }));
+        assert_eq!(dio(&[0.1; 5], 16000, &option.speed(2)), Err(WorldError::SignalTooShort { minimum: 10, actual: 5 }));
+        // Without decimation a few samples are enough, and ten are enough at any speed.
+        assert!(dio(&[0.1, 0.2, 0.3], 16000, &option.speed(1)).is_ok());
+        assert!(dio(&[0.1; 10], 16000, &option.speed(12)).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_sample_rate() {
+        let x      = vec![0.0; 256];
+        let option = HarvestOption::new();
+        assert_eq!(harvest(&x, 0, &option), Err(WorldError::InvalidSampleRate(0)));
+    }
+
+    #[test]
+    fn test_invalid_frame_period() {
+        let x          = vec![0.0; 256];
+        let fs         = 44100;
+        let mut option = DioOption::new();
+        option.frame_period = 0.0;
+        assert_eq!(dio(&x, fs, &option), Err(WorldError::InvalidFramePeriod(0.0)));
+    }
+
+    #[test]
+    fn test_non_finite_sample() {
+        let mut x  = vec![0.0; 256];
+        x[10]      = f64::NAN;
+        let fs     = 44100;
+        let option = DioOption::new();
+        assert_eq!(dio(&x, fs, &option), Err(WorldError::NonFiniteSample { name: "x", index: 10 }));
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let x                  = vec![0.0; 256];
+        let fs                 = 44100;
+        let temporal_positions = vec![0.0, 0.005, 0.010];
+        let f0                 = vec![0.0, 0.0];
+        assert_eq!(stonemask(&x, fs, &temporal_positions, &f0),
+                   Err(WorldError::LengthMismatch { name: "temporal_positions", expected: 2, actual: 3 }));
+    }
+
+    #[test]
+    fn test_synthesis_empty_spectrogram() {
+        let fs = 44100;
+        let spectrogram  = Spectrogram::with_fft_size(0, 2048);
+        let aperiodicity = Aperiodicity::with_fft_size(0, 2048);
+        assert_eq!(synthesis(&[], &spectrogram, &aperiodicity, 5.0, fs), Err(WorldError::EmptySpectrogram));
+    }
+
+    #[test]
+    fn test_synthesis_bins_mismatch() {
+        let fs           = 44100;
+        let f0           = vec![0.0, 0.0];
+        let spectrogram  = Spectrogram::with_fft_size(2, 2048);
+        let aperiodicity = Aperiodicity::with_fft_size(2, 1024);
+        assert_eq!(synthesis(&f0, &spectrogram, &aperiodicity, 5.0, fs),
+                   Err(WorldError::LengthMismatch { name: "aperiodicity bins", expected: 1025, actual: 513 }));
+    }
+
+    #[test]
+    fn test_synthesis_fractional_frame_period() {
+        let fs           = 16000;
+        let frame_period = 2.5;
+        let f0           = vec![0.0; 4];
+        let spectrogram  = Spectrogram::with_fft_size(4, 1024);
+        let aperiodicity = Aperiodicity::with_fft_size(4, 1024);
+        let y            = synthesis(&f0, &spectrogram, &aperiodicity, frame_period, fs).unwrap();
+        assert_eq!(y.len(), 160);
+    }
+
+    #[test]
+    fn test_synthesis_with_length() {
+        let x      = vec![0.0; 1000];
+        let fs     = 44100;
+        let option = DioOption::new();
+        let (temporal_positions, f0) = dio(&x, fs, &option).unwrap();
+        let frame_period = option.frame_period;
+        let option       = CheapTrickOption::new();
+        let spectrogram  = cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+        let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, option.fft_size(fs).unwrap(), &D4COption::new()).unwrap();
+        let y            = synthesis_with_length(&f0, &spectrogram, &aperiodicity, frame_period, fs, x.len()).unwrap();
+        assert_eq!(y.len(), x.len());
+    }
+
+    #[test]
+    fn test_synthesis_too_few_frames() {
+        let fs           = 16000;
+        let spectrogram  = Spectrogram::with_fft_size(1, 1024);
+        let aperiodicity = Aperiodicity::with_fft_size(1, 1024);
+        assert_eq!(synthesis(&[0.0], &spectrogram, &aperiodicity, 5.0, fs),
+                   Err(WorldError::TooFewFrames { minimum: 2, actual: 1 }));
+    }
+
+    #[test]
+    fn test_codec_custom_fft_size() {
+        let x                  = vec![0.0; 256];
+        let fs                 = 44100;
+        let temporal_positions = vec![0.0, 0.005];
+        let f0                 = vec![0.0, 0.0];
+        let mut option         = CheapTrickOption::new();
+        option.f0_floor        = 40.0;
+        let spectrogram        = cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+        assert_eq!(option.fft_size(fs).unwrap(), 4096);
+        assert_eq!(spectrogram.fft_size(), 4096);
+        let aperiodicity        = d4c(&x, fs, &temporal_positions, &f0, option.fft_size(fs).unwrap(), &D4COption::new()).unwrap();
+        assert_eq!(aperiodicity.fft_size(), 4096);
+        let coded_aperiodicity  = code_aperiodicity(&aperiodicity, fs).unwrap();
+        let decode_aperiodicity = decode_aperiodicity(&coded_aperiodicity, fs, option.fft_size(fs).unwrap()).unwrap();
+        assert_eq!(decode_aperiodicity.bins(), 2049);
+        let coded_spectrogram   = code_spectral_envelope(&spectrogram, fs, 64).unwrap();
+        let decode_spectrogram  = decode_spectral_envelope(&coded_spectrogram, fs, option.fft_size(fs).unwrap()).unwrap();
+        assert_eq!(decode_spectrogram.bins(), 2049);
+    }
+
+    #[test]
+    fn test_get_fft_size_for_cheaptrick() {
+        let fs         = 44100;
+        let option     = CheapTrickOption::new();
+        assert_eq!(get_fft_size_for_cheaptrick(fs, &option), Ok(2048));
+        assert_eq!(get_fft_size_for_cheaptrick(fs, &option.f0_floor(0.0)), Err(WorldError::InvalidOption { name: "f0_floor", value: 0.0 }));
+    }
+}
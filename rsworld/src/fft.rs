@@ -0,0 +1,137 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Sub, Mul};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    pub fn from_polar(r: f64, theta: f64) -> Self {
+        Complex { re: r * theta.cos(), im: r * theta.sin() }
+    }
+
+    pub fn conj(self) -> Self {
+        Complex { re: self.re, im: -self.im }
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex { re: self.re + other.re, im: self.im + other.im }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex { re: self.re - other.re, im: self.im - other.im }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex { re: self.re * other.re - self.im * other.im, im: self.re * other.im + self.im * other.re }
+    }
+}
+
+/// In-place complex DFT, `X[k] = sum x[n] exp(sign * 2 pi i k n / N)`, unnormalised.
+/// Radix-2 for powers of two, a direct sum otherwise.
+pub(crate) fn transform(x: &mut [Complex], sign: f64) {
+    let n = x.len();
+    if n <= 1 {
+        return;
+    }
+    if !n.is_power_of_two() {
+        let input = x.to_vec();
+        for (k, out) in x.iter_mut().enumerate() {
+            *out = input.iter().enumerate().fold(Complex::default(), |sum, (i, v)| {
+                sum + *v * Complex::from_polar(1.0, sign * 2.0 * PI * ((k * i) % n) as f64 / n as f64)
+            });
+        }
+        return;
+    }
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            x.swap(i, j);
+        }
+    }
+    let mut length = 2;
+    while length <= n {
+        let step = Complex::from_polar(1.0, sign * 2.0 * PI / length as f64);
+        for start in (0..n).step_by(length) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..length/2 {
+                let a = x[start+k];
+                let b = x[start+k+length/2] * w;
+                x[start+k]          = a + b;
+                x[start+k+length/2] = a - b;
+                w = w * step;
+            }
+        }
+        length *= 2;
+    }
+}
+
+/// Forward transform of a real signal, returning the `N/2+1` non-negative frequency bins.
+pub(crate) fn rfft(x: &[f64]) -> Vec<Complex> {
+    let mut buffer: Vec<Complex> = x.iter().map(|&v| Complex::new(v, 0.0)).collect();
+    transform(&mut buffer, -1.0);
+    buffer.truncate(x.len()/2+1);
+    buffer
+}
+
+/// Inverse of `rfft` for an even length `n`, including the 1/N normalisation.
+pub(crate) fn irfft(spectrum: &[Complex], n: usize) -> Vec<f64> {
+    let mut buffer = vec![Complex::default(); n];
+    for (k, v) in spectrum.iter().enumerate().take(n/2+1) {
+        buffer[k] = *v;
+        if k > 0 && k < n - k {
+            buffer[n-k] = v.conj();
+        }
+    }
+    transform(&mut buffer, 1.0);
+    buffer.iter().map(|v| v.re / n as f64).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Complex, transform, rfft, irfft};
+
+    #[test]
+    fn test_round_trip() {
+        for &n in &[8, 12, 64] {
+            let x: Vec<f64> = (0..n).map(|i| ((i * 7) % 5) as f64 - 2.0).collect();
+            let y = irfft(&rfft(&x), n);
+            for (x, y) in x.iter().zip(&y) {
+                assert!((x - y).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_radix2_matches_dft() {
+        let x: Vec<Complex> = (0..16).map(|i| Complex::new((i as f64).sin(), (i as f64 * 0.3).cos())).collect();
+        let mut fast = x.clone();
+        transform(&mut fast, -1.0);
+        for (k, fast) in fast.iter().enumerate() {
+            let slow = x.iter().enumerate().fold(Complex::default(), |sum, (i, v)| {
+                sum + *v * Complex::from_polar(1.0, -2.0 * std::f64::consts::PI * (k * i) as f64 / 16.0)
+            });
+            let error = *fast - slow;
+            assert!(error.re.hypot(error.im) < 1e-10);
+        }
+    }
+}
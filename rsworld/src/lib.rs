@@ -1,342 +1,179 @@
-use rsworld_sys::{
-    CheapTrick,
-    GetFFTSizeForCheapTrick,
-    CheapTrickOption,
-    GetNumberOfAperiodicities,
-    CodeAperiodicity,
-    DecodeAperiodicity,
-    CodeSpectralEnvelope,
-    DecodeSpectralEnvelope,
-    D4C,
-    D4COption,
-    Dio,
-    GetSamplesForDIO,
-    DioOption,
-    Harvest,
-    GetSamplesForHarvest,
-    HarvestOption,
-    StoneMask,
-    Synthesis,
+mod error;
+mod matrix;
+mod parameters;
+mod analyzer;
+mod options;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "ffi")]
+mod realtime;
+mod streaming;
+mod parameter_io;
+mod fft;
+mod mcep;
+mod mel;
+mod metrics;
+mod dtw;
+mod manipulation;
+mod contour;
+mod f0_cleanup;
+#[cfg(feature = "wav")]
+mod wav;
+#[cfg(feature = "native")]
+pub mod native;
+#[cfg(feature = "npy")]
+mod npy;
+#[cfg(test)]
+mod test_signals;
+
+pub use error::{WorldError, Result};
+pub use matrix::{Spectrogram, Aperiodicity, CodedSpectrogram, CodedAperiodicity, MelCepstrum, MelSpectrogram, Mfcc};
+pub use parameters::WorldParameters;
+pub use analyzer::{Analyzer, F0Method};
+pub use options::{DioOption, HarvestOption, CheapTrickOption, D4COption};
+#[cfg(feature = "ffi")]
+pub use realtime::RealtimeSynthesizer;
+pub use streaming::StreamingAnalyzer;
+pub use mcep::{mcep_alpha, sp_to_mcep, mcep_to_sp, sp_to_mgc, mgc_to_sp};
+pub use mel::{Compression, MelOption, mel_spectrogram, mel_spectrogram_from_signal, mfcc};
+pub use metrics::{
+    Alignment,
+    mel_cepstral_distortion,
+    mel_cepstral_distortion_from_spectrogram,
+    f0_rmse_cents,
+    vuv_error_rate,
+    log_f0_correlation,
+    log_spectral_distance,
+};
+pub use dtw::{Features, Dtw, StepPattern, Band, WarpingPath, warp_parameters};
+pub use manipulation::{semitones_to_ratio, shift_pitch, shift_pitch_semitones, shift_formants, stretch_time, change_frame_period};
+pub use contour::{Interpolation, continuous_log_f0, discontinuous_f0};
+pub use f0_cleanup::{F0Cleanup, F0CleanupReport, Smoothing};
+pub use parameter_io::{
+    ParameterHeader,
+    read_f0,
+    write_f0,
+    read_spectral_envelope,
+    read_coded_spectral_envelope,
+    write_spectral_envelope,
+    write_coded_spectral_envelope,
+    read_aperiodicity,
+    read_coded_aperiodicity,
+    write_aperiodicity,
+    write_coded_aperiodicity,
+};
+#[cfg(feature = "wav")]
+pub use wav::{read_wav, read_wav_from, write_wav, write_wav_to, ChannelSelection, WavFormat, WavWriteOption};
+#[cfg(feature = "npy")]
+pub use npy::{read_npy_vector, write_npy_vector, read_npz, write_npz};
+
+// The analysis and synthesis functions call WORLD's C++ through `ffi`. Without
+// it they are the `native` ports, so everything built on them keeps working
+// on targets where WORLD cannot be compiled.
+#[cfg(feature = "ffi")]
+pub use ffi::{
+    get_fft_size_for_cheaptrick,
+    cheaptrick,
+    get_number_of_aperiodicities,
+    code_aperiodicity,
+    decode_aperiodicity,
+    code_spectral_envelope,
+    decode_spectral_envelope,
+    d4c,
+    dio,
+    harvest,
+    stonemask,
+    synthesis,
+    synthesis_with_length,
+};
+#[cfg(all(feature = "native", not(feature = "ffi")))]
+pub use native::{
+    get_fft_size_for_cheaptrick,
+    cheaptrick,
+    get_number_of_aperiodicities,
+    code_aperiodicity,
+    decode_aperiodicity,
+    code_spectral_envelope,
+    decode_spectral_envelope,
+    d4c,
+    dio,
+    harvest,
+    stonemask,
+    synthesis,
+    synthesis_with_length,
+};
+#[cfg(not(any(feature = "ffi", feature = "native")))]
+compile_error!("rsworld needs the `ffi` or the `native` feature");
+
+use error::{
+    check_fs,
+    check_frame_period,
+    check_fft_size,
+    check_length,
+    check_same_length,
+    check_finite,
 };
 
-pub fn cheaptrick(x: &Vec<f64>, fs: i32, temporal_positions: &Vec<f64>, f0: &Vec<f64>, option: &mut CheapTrickOption) -> Vec<Vec<f64>> {
-    let x_length:  i32 = x.len()  as i32;
-    let f0_length: i32 = f0.len() as i32;
-    unsafe {
-	GetFFTSizeForCheapTrick(fs, option as *mut _);
-    }
-    let mut spectrogram     = vec![vec![0.0; (option.fft_size/2+1) as usize]; f0_length as usize];
-    let mut spectrogram_ptr = spectrogram.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
-    let spectrogram_ptr = spectrogram_ptr.as_mut_ptr();
-    unsafe {
-	CheapTrick(x.as_ptr(), x_length, fs, temporal_positions.as_ptr(), f0.as_ptr(), f0_length, option as *const _, spectrogram_ptr);
-    }
-    spectrogram
-}
-
-pub fn get_number_of_aperiodicities(fs: i32) -> i32 {
-    unsafe {
-	GetNumberOfAperiodicities(fs)
-    }
-}
-
-pub fn code_aperiodicity(aperiodicity: &Vec<Vec<f64>>, f0_length: i32, fs: i32) -> Vec<Vec<f64>> {
-    let mut cheaptrick_option = CheapTrickOption::new(fs);
-    unsafe {
-	GetFFTSizeForCheapTrick(fs, &mut cheaptrick_option as *mut _);
-    }
-    let fft_size  = cheaptrick_option.fft_size;
-    let aperiodicity_ptr           = aperiodicity.iter().map(|inner| inner.as_ptr()).collect::<Vec<_>>();
-    let aperiodicity_ptr           = aperiodicity_ptr.as_ptr();
-    let n_aperiodicity;
-    unsafe {
-	n_aperiodicity             = GetNumberOfAperiodicities(fs);
-    }
-    let mut coded_aperiodicity     = vec![vec![0.0; n_aperiodicity as usize]; f0_length as usize];
-    let mut coded_aperiodicity_ptr = coded_aperiodicity.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
-    let coded_aperiodicity_ptr     = coded_aperiodicity_ptr.as_mut_ptr();
-    unsafe {
-	CodeAperiodicity(aperiodicity_ptr, f0_length, fs, fft_size, coded_aperiodicity_ptr);
-    }
-    coded_aperiodicity
-}
-
-pub fn decode_aperiodicity(coded_aperiodicity: &Vec<Vec<f64>>, f0_length: i32, fs: i32) -> Vec<Vec<f64>> {
-    let mut cheaptrick_option = CheapTrickOption::new(fs);
-    unsafe {
-	GetFFTSizeForCheapTrick(fs, &mut cheaptrick_option as *mut _);
-    }
-    let fft_size  = cheaptrick_option.fft_size;
-    let coded_aperiodicity_ptr = coded_aperiodicity.iter().map(|inner| inner.as_ptr()).collect::<Vec<_>>();
-    let coded_aperiodicity_ptr = coded_aperiodicity_ptr.as_ptr();
-    let mut aperiodicity       = vec![vec![0.0; (fft_size/2+1) as usize]; f0_length as usize];
-    let mut aperiodicity_ptr   = aperiodicity.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
-    let aperiodicity_ptr       = aperiodicity_ptr.as_mut_ptr();
-    unsafe {
-	DecodeAperiodicity(coded_aperiodicity_ptr, f0_length, fs, fft_size, aperiodicity_ptr);
-    }
-    aperiodicity
-}
-
-pub fn code_spectral_envelope(spectrogram: &Vec<Vec<f64>>, f0_length: i32, fs: i32, fft_size: i32, number_of_dimensions: i32) -> Vec<Vec<f64>> {
-    let spectrogram_ptr = spectrogram.iter().map(|inner| inner.as_ptr()).collect::<Vec<_>>();
-    let spectrogram_ptr = spectrogram_ptr.as_ptr();
-    let mut coded_spectral_envelope     = vec![vec![0.0; number_of_dimensions as usize]; f0_length as usize];
-    let mut coded_spectral_envelope_ptr = coded_spectral_envelope.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
-    let coded_spectral_envelope_ptr = coded_spectral_envelope_ptr.as_mut_ptr();
-    unsafe {
-	CodeSpectralEnvelope(spectrogram_ptr, f0_length, fs, fft_size, number_of_dimensions, coded_spectral_envelope_ptr);
-
-    }
-    coded_spectral_envelope
-}
-
-pub fn decode_spectral_envelope(coded_spectrogram: &Vec<Vec<f64>>, f0_length: i32, fs: i32, fft_size: i32) -> Vec<Vec<f64>> {
-    let number_of_dimensions = coded_spectrogram[0].len() as i32;
-    let mut spectrogram      = vec![vec![0.0; (fft_size/2+1) as usize]; f0_length as usize];
-    let mut spectrogram_ptr = spectrogram.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
-    let spectrogram_ptr = spectrogram_ptr.as_mut_ptr();
-    let coded_spectrogram_ptr = coded_spectrogram.iter().map(|inner| inner.as_ptr()).collect::<Vec<_>>();
-    let coded_spectrogram_ptr = coded_spectrogram_ptr.as_ptr();
-    unsafe {
-	DecodeSpectralEnvelope(coded_spectrogram_ptr, f0_length, fs, fft_size, number_of_dimensions, spectrogram_ptr);
-    }
-    spectrogram
-}
-
-pub fn d4c(x: &Vec<f64>, fs: i32, temporal_positions: &Vec<f64>, f0: &Vec<f64>, option: &D4COption) -> Vec<Vec<f64>> {
-    let x_length  = x.len()  as i32;
-    let f0_length = f0.len() as i32;
-    let mut cheaptrick_option = CheapTrickOption::new(fs);
-    unsafe {
-	GetFFTSizeForCheapTrick(fs, &mut cheaptrick_option as *mut _);
-    }
-    let fft_size  = cheaptrick_option.fft_size;
-    let mut aperiodicity     = vec![vec![0.0; (fft_size/2+1) as usize]; f0_length as usize];
-    let mut aperiodicity_ptr = aperiodicity.iter_mut().map(|inner| inner.as_mut_ptr()).collect::<Vec<_>>();
-    let aperiodicity_ptr     = aperiodicity_ptr.as_mut_ptr();
-    unsafe {
-	D4C(x.as_ptr(), x_length, fs, temporal_positions.as_ptr(), f0.as_ptr(), f0_length, fft_size, option as *const _, aperiodicity_ptr);
-    }
-    aperiodicity
-}
-
-pub fn dio(x: &Vec<f64>, fs: i32, option: &DioOption) -> (Vec<f64>, Vec<f64>) {
-    let x_length = x.len() as i32;
-    let f0_length: usize;
-    unsafe {
-        f0_length = GetSamplesForDIO(fs, x_length, option.frame_period) as usize;
-    }
-    let mut temporal_positions: Vec<f64> = vec![0.0; f0_length];
-    let mut f0:                 Vec<f64> = vec![0.0; f0_length];
-    unsafe {
-        Dio(x.as_ptr(), x_length, fs, option as *const _, temporal_positions.as_mut_ptr(), f0.as_mut_ptr());
-    }
-    (temporal_positions, f0)
+fn check_f0(temporal_positions: &[f64], f0: &[f64]) -> Result<i32> {
+    check_same_length("temporal_positions", f0.len(), temporal_positions.len())?;
+    check_finite("temporal_positions", temporal_positions)?;
+    check_finite("f0", f0)?;
+    check_length("f0", f0.len())
 }
 
-pub fn harvest(x: &Vec<f64>, fs: i32, option: &HarvestOption) -> (Vec<f64>, Vec<f64>) {
-    let x_length = x.len() as i32;
-    let f0_length: usize;
-    unsafe {
-        f0_length = GetSamplesForHarvest(fs, x_length, option.frame_period) as usize;
+// Below 12 kHz WORLD codes no aperiodicity bands, which no matrix can hold.
+fn check_aperiodicities(number_of_aperiodicities: i32) -> Result<usize> {
+    if number_of_aperiodicities <= 0 {
+        return Err(WorldError::InvalidDimensions(number_of_aperiodicities));
     }
-    let mut temporal_positions: Vec<f64> = vec![0.0; f0_length];
-    let mut f0:                 Vec<f64> = vec![0.0; f0_length];
-    unsafe {
-        Harvest(x.as_ptr(), x_length, fs, option as *const _, temporal_positions.as_mut_ptr(), f0.as_mut_ptr());
-    }
-    (temporal_positions, f0)
+    Ok(number_of_aperiodicities as usize)
 }
 
-pub fn stonemask(x: &Vec<f64>, fs: i32, temporal_positions: &Vec<f64>, f0: &Vec<f64>) -> Vec<f64> {
-    let x_length  = x.len() as i32;
-    let f0_length = f0.len();
-    let mut refined_f0 = vec![0.0; f0_length];
-    unsafe {
-	StoneMask(x.as_ptr(), x_length, fs, temporal_positions.as_ptr(), f0.as_ptr(), f0_length as i32, refined_f0.as_mut_ptr());
-    }
-    refined_f0
+// Returns the F0 length and FFT size.
+fn check_synthesis(f0: &[f64], spectrogram: &Spectrogram, aperiodicity: &Aperiodicity, frame_period: f64, fs: i32) -> Result<(i32, i32)> {
+    check_fs(fs)?;
+    check_frame_period(frame_period)?;
+    check_finite("f0", f0)?;
+    let f0_length = check_length("f0", f0.len())?;
+    if spectrogram.is_empty() {
+	return Err(WorldError::EmptySpectrogram);
+    }
+    // Synthesis extrapolates the contour from its last two frames.
+    if f0.len() < 2 {
+	return Err(WorldError::TooFewFrames { minimum: 2, actual: f0.len() });
+    }
+    let fft_size  = check_length("spectrogram", spectrogram.fft_size())?;
+    check_fft_size(fft_size)?;
+    check_same_length("spectrogram",       f0.len(),           spectrogram.frames())?;
+    check_same_length("aperiodicity",      f0.len(),           aperiodicity.frames())?;
+    check_same_length("aperiodicity bins", spectrogram.bins(), aperiodicity.bins())?;
+    Ok((f0_length, fft_size))
 }
 
-pub fn synthesis(f0: &Vec<f64>, spectrogram: &Vec<Vec<f64>>, aperiodicity: &Vec<Vec<f64>>, frame_period: f64, fs: i32) -> Vec<f64> {
-    let f0_length        = f0.len() as i32;
-    let spectrogram_ptr  = spectrogram.iter().map(|inner| inner.as_ptr()).collect::<Vec<_>>();
-    let spectrogram_ptr  = spectrogram_ptr.as_ptr();
-    let aperiodicity_ptr = aperiodicity.iter().map(|inner| inner.as_ptr()).collect::<Vec<_>>();
-    let aperiodicity_ptr = aperiodicity_ptr.as_ptr();
-    let fft_size         = (spectrogram[0].len()-1)*2;
-    let y_length         = f0_length * frame_period as i32 * fs / 1000;
-    let mut y            = vec![0.0; y_length as usize];
-    unsafe {
-	Synthesis(f0.as_ptr(), f0_length as i32, spectrogram_ptr, aperiodicity_ptr, fft_size as i32, frame_period, fs, y_length as i32, y.as_mut_ptr())
-    }
-    y
+pub fn get_samples_for_synthesis(f0_length: usize, frame_period: f64, fs: i32) -> usize {
+    // Counted in whole microseconds so that e.g. 3 * 5.8 ms * 16 kHz does not
+    // truncate to one sample short through the rounding of 5.8.
+    let frame_period = (frame_period * 1000.0).round().max(0.0) as u128;
+    (f0_length as u128 * frame_period * fs.max(0) as u128 / 1_000_000) as usize
 }
 
 #[cfg(test)]
 mod tests {
-    // CheapTrick test
-    use crate::{cheaptrick, CheapTrickOption};
-
-    #[test]
-    fn test_cheaptrick() {
-	let x                  = vec![0.0; 256];
-	let fs 		       = 44100;
-        let temporal_positions = vec![0.0, 0.005];
-        let f0                 = vec![0.0, 0.0];
-	let mut option         = CheapTrickOption::new(fs);
-	let spectrogram = cheaptrick(&x, fs, &temporal_positions, &f0, &mut option);
-	assert_eq!(spectrogram.len(), f0.len());
-	assert_eq!(spectrogram[0].len(), (option.fft_size/2+1) as usize);
-    }
-
-    // Codec test
-    use crate::{
-	get_number_of_aperiodicities,
-	code_aperiodicity,
-	decode_aperiodicity,
-	code_spectral_envelope,
-	decode_spectral_envelope
-    };
-
-    #[test]
-    fn test_get_number_of_aperiodicities() {
-	let fs = 44100;
-	assert_eq!(get_number_of_aperiodicities(fs), 5);
-    }
-
-    #[test]
-    fn test_code_aperiodicity() {
-	let x                  = vec![0.0; 256];
-	let fs                 = 44100 as i32;
-        let temporal_positions = vec![0.0, 0.005];
-        let f0                 = vec![0.0, 0.0];
-	let option             = D4COption::new();
-
-	let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, &option);
-	let coded_aperiodicity = code_aperiodicity(&aperiodicity, f0.len() as i32, fs);
-	assert_eq!(coded_aperiodicity.len(),    f0.len());
-	assert_eq!(coded_aperiodicity[0].len(), get_number_of_aperiodicities(fs) as usize);
-    }
-
-    #[test]
-    fn test_decode_aperiodicity() {
-	let x                  = vec![0.0; 256];
-	let fs                 = 44100 as i32;
-        let temporal_positions = vec![0.0, 0.005];
-        let f0                 = vec![0.0, 0.0];
-	let option             = D4COption::new();
-
-	let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, &option);
-	let coded_aperiodicity  = code_aperiodicity(&aperiodicity, f0.len() as i32, fs);
-	let decode_aperiodicity = decode_aperiodicity(&coded_aperiodicity, f0.len() as i32, fs);
-	assert_eq!(decode_aperiodicity.len(), f0.len());
-	assert_eq!(decode_aperiodicity[0].len(), (2048/2+1) as usize);
-	assert_eq!(decode_aperiodicity[0][0], 0.999999999999);
-    }
-
-    #[test]
-    fn test_code_spectral_envelope() {
-	let x                  = vec![0.0; 256];
-	let fs 		       = 44100;
-        let temporal_positions = vec![0.0, 0.005];
-        let f0                 = vec![0.0, 0.0];
-	let mut option         = CheapTrickOption::new(fs);
-	let spectrogram        = cheaptrick(&x, fs, &temporal_positions, &f0, &mut option);
-	let number_of_dimensions = 256;
-	let coded_spectrogram  = code_spectral_envelope(&spectrogram, f0.len() as i32, fs, option.fft_size, number_of_dimensions);
-	assert_eq!(coded_spectrogram.len(), f0.len());
-	assert_eq!(coded_spectrogram[0].len(), number_of_dimensions as usize);
-    }
-
-    #[test]
-    fn test_decode_spectral_envelope() {
-	let x                  = vec![0.0; 256];
-	let fs 		       = 44100;
-        let temporal_positions = vec![0.0, 0.005];
-        let f0                 = vec![0.0, 0.0];
-	let mut option         = CheapTrickOption::new(fs);
-	let spectrogram        = cheaptrick(&x, fs, &temporal_positions, &f0, &mut option);
-	let number_of_dimensions = 256;
-	let coded_spectrogram  = code_spectral_envelope(&spectrogram, f0.len() as i32, fs, option.fft_size, number_of_dimensions);
-	let spectrogram        = decode_spectral_envelope(&coded_spectrogram, f0.len() as i32, fs, option.fft_size);
-	assert_eq!(spectrogram.len(),    f0.len());
-	assert_eq!(spectrogram[0].len(), (option.fft_size/2+1) as usize);
-    }
-
-    // D4C test
-    use crate::{d4c, D4COption};
-
-    #[test]
-    fn test_d4c() {
-	let x                  = vec![0.0; 256];
-	let fs                 = 44100 as i32;
-        let temporal_positions = vec![0.0, 0.005];
-        let f0                 = vec![0.0, 0.0];
-	let option             = D4COption::new();
-	let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, &option);
-	assert_eq!(aperiodicity.len(), f0.len());
-	assert_eq!(aperiodicity[0].len(), (2048/2+1) as usize);
-	assert_eq!(aperiodicity[0][0], 0.999999999999);
-    }
-
-    // DIO test
-    use crate::{dio, DioOption};
+    use crate::{Aperiodicity, CodedAperiodicity, WorldError, get_samples_for_synthesis, code_aperiodicity, decode_aperiodicity};
 
     #[test]
-    fn test_dio() {
-        let x  = vec![0.0; 256];
-        let fs = 44100;
-        let option = DioOption::new();
-        let (temporal_positions, f0) = dio(&x, fs, &option);
-        assert_eq!(temporal_positions, vec![0.0, 0.005]);
-        assert_eq!(f0,                 vec![0.0, 0.0]);
+    fn test_get_samples_for_synthesis() {
+        assert_eq!(get_samples_for_synthesis(2,   5.0, 44100), 441);
+        assert_eq!(get_samples_for_synthesis(3,   2.5, 16000), 120);
+        assert_eq!(get_samples_for_synthesis(3,   5.8, 16000), 278);
+        assert_eq!(get_samples_for_synthesis(100, 5.8, 16000), 9280);
     }
 
-    // Harvest test
-    use crate::{harvest, HarvestOption};
-
-    #[test]
-    fn test_harvest() {
-        let x  = vec![0.0; 256];
-        let fs = 44100;
-        let option = HarvestOption::new();
-        let (temporal_positions, f0) = harvest(&x, fs, &option);
-        assert_eq!(temporal_positions, vec![0.0, 0.005]);
-        assert_eq!(f0,                 vec![0.0, 0.0]);
-    }
-
-    // StoneMask test
-    use crate::stonemask;
-
-    #[test]
-    fn test_stonemask() {
-        let x  = vec![0.0; 256];
-        let fs = 44100;
-        let option = DioOption::new();
-        let (temporal_positions, f0) = dio(&x, fs, &option);
-	let refined_f0 = stonemask(&x, fs, &temporal_positions, &f0);
-	assert_eq!(refined_f0, vec![0.0, 0.0]);
-    }
-
-    // Synthesis test
-    use crate::synthesis;
-
     #[test]
-    fn test_synthesis() {
-        let x      = vec![0.0; 256];
-        let fs     = 44100;
-        let option = DioOption::new();
-        let (temporal_positions, f0) = dio(&x, fs, &option);
-	let frame_period = option.frame_period;
-	let f0           = stonemask(&x, fs, &temporal_positions, &f0);
-	let mut option   = CheapTrickOption::new(fs);
-	let spectrogram  = cheaptrick(&x, fs, &temporal_positions, &f0, &mut option);
-	let option       = D4COption::new();
-	let aperiodicity = d4c(&x, fs, &temporal_positions, &f0, &option);
-	let y            = synthesis(&f0, &spectrogram, &aperiodicity, frame_period, fs);
-	let y_length     = f0.len() as i32 * frame_period as i32 * fs / 1000;
-	assert_eq!(y.len(), y_length as usize);
+    fn test_no_aperiodicity_bands() {
+        let aperiodicity = Aperiodicity::with_fft_size(2, 512);
+        assert_eq!(code_aperiodicity(&aperiodicity, 8000), Err(WorldError::InvalidDimensions(0)));
+        assert_eq!(decode_aperiodicity(&CodedAperiodicity::zeros(2, 1), 8000, 512), Err(WorldError::InvalidDimensions(0)));
+        assert_eq!(code_aperiodicity(&aperiodicity, 12000).unwrap().dimensions(), 1);
     }
 }
@@ -0,0 +1,227 @@
+use crate::{WorldParameters, Spectrogram, Aperiodicity};
+use crate::error::{WorldError, Result, check_frame_period, check_finite, check_positive};
+
+fn check_ratio(name: &'static str, ratio: f64) -> Result<()> {
+    if !(ratio.is_finite() && ratio > 0.0) {
+        return Err(WorldError::InvalidOption { name, value: ratio });
+    }
+    Ok(())
+}
+
+fn interpolate(a: f64, b: f64, weight: f64) -> f64 {
+    a + (b - a) * weight
+}
+
+pub fn semitones_to_ratio(semitones: f64) -> f64 {
+    2f64.powf(semitones / 12.0)
+}
+
+/// Multiplies every voiced frame by `ratio`; unvoiced frames stay 0.0.
+pub fn shift_pitch(f0: &[f64], ratio: f64) -> Result<Vec<f64>> {
+    check_ratio("ratio", ratio)?;
+    check_finite("f0", f0)?;
+    Ok(f0.iter().map(|&v| v * ratio).collect())
+}
+
+pub fn shift_pitch_semitones(f0: &[f64], semitones: f64) -> Result<Vec<f64>> {
+    shift_pitch(f0, semitones_to_ratio(semitones))
+}
+
+/// Moves spectral envelope features by `ratio` along the frequency axis, so 1.2
+/// raises formants by 20%. Rows are warped in the log domain, so every bin must
+/// be positive and finite; bins that would read past Nyquist repeat the last bin.
+pub fn shift_formants(spectrogram: &Spectrogram, ratio: f64) -> Result<Spectrogram> {
+    check_ratio("ratio", ratio)?;
+    let bins = spectrogram.bins();
+    check_positive("spectrogram", spectrogram.as_slice())?;
+    let mut shifted      = spectrogram.clone();
+    let mut log_spectrum = vec![0.0; bins];
+    for (frame, row) in spectrogram.rows().enumerate() {
+        log_spectrum.iter_mut().zip(row).for_each(|(log, v)| *log = v.ln());
+        for (bin, out) in shifted.row_mut(frame).iter_mut().enumerate() {
+            let source = (bin as f64 / ratio).min((bins-1) as f64);
+            let index  = source.floor() as usize;
+            let next   = (index+1).min(bins-1);
+            *out = interpolate(log_spectrum[index], log_spectrum[next], source - index as f64).exp();
+        }
+    }
+    Ok(shifted)
+}
+
+// Samples the parameter set at fractional source frames. F0 is only interpolated
+// between two voiced frames; otherwise the nearest frame decides voicing.
+// Envelopes are interpolated in the log domain, so every bin must be positive;
+// aperiodicity is interpolated linearly.
+pub(crate) fn resample_frames(parameters: &WorldParameters, positions: &[f64], frame_period: f64) -> Result<WorldParameters> {
+    check_positive("spectrogram", parameters.spectrogram().as_slice())?;
+    let frames           = parameters.frames();
+    let source_f0        = parameters.f0();
+    let source_sp        = parameters.spectrogram();
+    let source_ap        = parameters.aperiodicity();
+    let mut f0           = Vec::with_capacity(positions.len());
+    let mut spectrogram  = Spectrogram::zeros(positions.len(), source_sp.bins());
+    let mut aperiodicity = Aperiodicity::zeros(positions.len(), source_ap.bins());
+    for (frame, &position) in positions.iter().enumerate() {
+        let position = position.max(0.0).min((frames-1) as f64);
+        let index    = position.floor() as usize;
+        let next     = (index+1).min(frames-1);
+        let weight   = position - index as f64;
+        f0.push(if source_f0[index] > 0.0 && source_f0[next] > 0.0 {
+            interpolate(source_f0[index], source_f0[next], weight)
+        } else if weight <= 0.5 {
+            source_f0[index]
+        } else {
+            source_f0[next]
+        });
+        let (a, b) = (source_sp.row(index), source_sp.row(next));
+        for (bin, out) in spectrogram.row_mut(frame).iter_mut().enumerate() {
+            *out = interpolate(a[bin].ln(), b[bin].ln(), weight).exp();
+        }
+        let (a, b) = (source_ap.row(index), source_ap.row(next));
+        for (bin, out) in aperiodicity.row_mut(frame).iter_mut().enumerate() {
+            *out = interpolate(a[bin], b[bin], weight);
+        }
+    }
+    let temporal_positions = (0..positions.len()).map(|i| i as f64 * frame_period / 1000.0).collect();
+    WorldParameters::new(f0, temporal_positions, spectrogram, aperiodicity, parameters.fs(), frame_period)
+}
+
+/// Resamples the frame axis so the result lasts `rate` times as long at the same
+/// `frame_period`. Pitch and formants are unchanged. Every envelope bin must be
+/// positive and finite.
+pub fn stretch_time(parameters: &WorldParameters, rate: f64) -> Result<WorldParameters> {
+    check_ratio("rate", rate)?;
+    if parameters.frames() == 0 {
+        return Err(WorldError::EmptySpectrogram);
+    }
+    let frames    = ((parameters.frames() as f64 * rate).round() as usize).max(1);
+    let positions: Vec<f64> = (0..frames).map(|i| i as f64 / rate).collect();
+    let stretched = resample_frames(parameters, &positions, parameters.frame_period())?;
+    let length    = (parameters.signal_length() as f64 * rate).round() as usize;
+    Ok(stretched.with_signal_length(length))
+}
+
+/// Resamples the parameter set onto a grid of `frame_period` milliseconds
+/// up to the last source frame, e.g. to turn 5 ms analysis into 12.5 ms frames.
+/// Converting to the same period keeps every frame. Every envelope bin must be
+/// positive and finite.
+pub fn change_frame_period(parameters: &WorldParameters, frame_period: f64) -> Result<WorldParameters> {
+    check_frame_period(frame_period)?;
+    if parameters.frames() == 0 {
+        return Err(WorldError::EmptySpectrogram);
+    }
+    // In whole microseconds, like get_samples_for_synthesis.
+    let last      = (parameters.frames()-1) as u128 * (parameters.frame_period() * 1000.0).round() as u128;
+    let frames    = (last / ((frame_period * 1000.0).round() as u128).max(1)) as usize + 1;
+    let length    = parameters.signal_length();
+    let ratio     = frame_period / parameters.frame_period();
+    let positions: Vec<f64> = (0..frames).map(|i| i as f64 * ratio).collect();
+    Ok(resample_frames(parameters, &positions, frame_period)?.with_signal_length(length))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{WorldParameters, Spectrogram, Aperiodicity, WorldError, semitones_to_ratio, shift_pitch, shift_pitch_semitones, shift_formants, stretch_time, change_frame_period};
+
+    fn parameters() -> WorldParameters {
+        let mut spectrogram  = Spectrogram::with_fft_size(4, 1024);
+        let mut aperiodicity = Aperiodicity::with_fft_size(4, 1024);
+        spectrogram.as_mut_slice().iter_mut().enumerate().for_each(|(i, v)| *v = 1e-4 / (1.0 + (i % 513) as f64));
+        aperiodicity.as_mut_slice().iter_mut().enumerate().for_each(|(i, v)| *v = (i / 513) as f64 / 4.0);
+        WorldParameters::new(vec![100.0, 0.0, 200.0, 220.0], vec![0.0, 0.005, 0.01, 0.015], spectrogram, aperiodicity, 16000, 5.0).unwrap()
+    }
+
+    #[test]
+    fn test_shift_pitch() {
+        assert!((semitones_to_ratio(12.0) - 2.0).abs() < 1e-12);
+        assert_eq!(shift_pitch(&[100.0, 0.0], 1.5), Ok(vec![150.0, 0.0]));
+        let shifted = shift_pitch_semitones(&[100.0, 0.0], -12.0).unwrap();
+        assert!((shifted[0] - 50.0).abs() < 1e-9);
+        assert_eq!(shifted[1], 0.0);
+        assert_eq!(shift_pitch(&[100.0], 0.0), Err(WorldError::InvalidOption { name: "ratio", value: 0.0 }));
+    }
+
+    #[test]
+    fn test_shift_formants() {
+        let spectrogram = Spectrogram::from_vec(1, 5, vec![1.0, 2.0, 4.0, 8.0, 16.0]).unwrap();
+        let shifted     = shift_formants(&spectrogram, 2.0).unwrap();
+        let expected    = [1.0, 2f64.sqrt(), 2.0, 8f64.sqrt(), 4.0];
+        for (v, e) in shifted.row(0).iter().zip(&expected) {
+            assert!((v - e).abs() < 1e-12);
+        }
+        let lowered = shift_formants(&spectrogram, 0.5).unwrap();
+        assert!(lowered.row(0)[2..].iter().all(|v| (v - 16.0).abs() < 1e-12));
+        let silent = Spectrogram::from_vec(1, 3, vec![1.0, 0.0, 1.0]).unwrap();
+        assert_eq!(shift_formants(&silent, 1.2), Err(WorldError::NonPositiveValue { name: "spectrogram", index: 1 }));
+    }
+
+    #[test]
+    fn test_stretch_time() {
+        let parameters = parameters();
+        let stretched  = stretch_time(&parameters, 2.0).unwrap();
+        assert_eq!(stretched.frames(), 8);
+        assert_eq!(stretched.frame_period(), 5.0);
+        assert_eq!(stretched.signal_length(), 2*parameters.signal_length());
+        // Interpolation between a voiced and an unvoiced frame takes the nearest one.
+        assert_eq!(stretched.f0(), &[100.0, 100.0, 0.0, 0.0, 200.0, 210.0, 220.0, 220.0]);
+        assert_eq!(stretched.aperiodicity().row(1)[0], 0.125);
+        assert!(stretched.synthesize().unwrap().iter().all(|v| v.is_finite()));
+
+        let compressed = stretch_time(&parameters, 0.5).unwrap();
+        assert_eq!(compressed.f0(), &[100.0, 200.0]);
+    }
+
+    #[test]
+    fn test_stretch_time_non_positive_bin() {
+        let parameters = parameters();
+        let mut spectrogram = parameters.spectrogram().clone();
+        spectrogram[(0, 3)] = -1e-4;
+        let parameters = WorldParameters::new(parameters.f0().to_vec(), parameters.temporal_positions().to_vec(), spectrogram,
+                                              parameters.aperiodicity().clone(), 16000, 5.0).unwrap();
+        assert_eq!(stretch_time(&parameters, 2.0), Err(WorldError::NonPositiveValue { name: "spectrogram", index: 3 }));
+    }
+
+    #[test]
+    fn test_change_frame_period() {
+        let parameters = parameters().with_signal_length(240);
+        let coarse     = change_frame_period(&parameters, 10.0).unwrap();
+        assert_eq!(coarse.frames(), 2);
+        assert_eq!(coarse.frame_period(), 10.0);
+        assert_eq!(coarse.temporal_positions(), &[0.0, 0.01]);
+        assert_eq!(coarse.f0(), &[100.0, 200.0]);
+        assert_eq!(coarse.signal_length(), 240);
+        assert_eq!(coarse.synthesize().unwrap().len(), 240);
+
+        let fine = change_frame_period(&parameters, 2.5).unwrap();
+        assert_eq!(fine.frames(), 7);
+        assert_eq!(fine.f0(), &[100.0, 100.0, 0.0, 0.0, 200.0, 210.0, 220.0]);
+        // Envelopes are interpolated geometrically.
+        let (a, b) = (parameters.spectrogram().row(2)[0], parameters.spectrogram().row(3)[0]);
+        assert!((fine.spectrogram().row(5)[0] - (a * b).sqrt()).abs() < 1e-15);
+        assert_eq!(change_frame_period(&parameters, 0.0), Err(WorldError::InvalidFramePeriod(0.0)));
+    }
+
+    #[test]
+    fn test_change_frame_period_identity() {
+        let parameters = parameters();
+        let same       = change_frame_period(&parameters, 5.0).unwrap();
+        assert_eq!(same.frames(),             parameters.frames());
+        assert_eq!(same.signal_length(),      parameters.signal_length());
+        assert_eq!(same.temporal_positions(), parameters.temporal_positions());
+        assert_eq!(same.f0(),                 parameters.f0());
+        assert_eq!(same.aperiodicity(),       parameters.aperiodicity());
+        for (v, reference) in same.spectrogram().as_slice().iter().zip(parameters.spectrogram().as_slice()) {
+            assert!((v - reference).abs() <= 1e-15 * reference);
+        }
+    }
+
+    #[test]
+    fn test_change_frame_period_zero_bin() {
+        let parameters = parameters();
+        let mut spectrogram = parameters.spectrogram().clone();
+        spectrogram[(2, 0)] = 0.0;
+        let parameters = WorldParameters::new(parameters.f0().to_vec(), parameters.temporal_positions().to_vec(), spectrogram,
+                                              parameters.aperiodicity().clone(), 16000, 5.0).unwrap();
+        assert_eq!(change_frame_period(&parameters, 5.0), Err(WorldError::NonPositiveValue { name: "spectrogram", index: 2*513 }));
+    }
+}
@@ -0,0 +1,275 @@
+use std::ops::{Index, IndexMut};
+use std::slice::{ChunksExact, ChunksExactMut};
+
+use crate::error::{WorldError, Result, check_same_length};
+
+macro_rules! matrix {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $name {
+            data:    Vec<f64>,
+            frames:  usize,
+            columns: usize,
+        }
+
+        impl $name {
+            /// Panics if `columns` is 0, which `from_vec` reports as an error.
+            pub fn zeros(frames: usize, columns: usize) -> Self {
+                assert!(columns > 0, "{} needs at least one column", stringify!($name));
+                $name { data: vec![0.0; frames*columns], frames, columns }
+            }
+
+            pub fn from_vec(frames: usize, columns: usize, data: Vec<f64>) -> Result<Self> {
+                if columns == 0 {
+                    return Err(WorldError::InvalidDimensions(0));
+                }
+                check_same_length(stringify!($name), frames*columns, data.len())?;
+                Ok($name { data, frames, columns })
+            }
+
+            pub fn from_rows(rows: &[Vec<f64>]) -> Result<Self> {
+                let columns = rows.first().map_or(0, |inner| inner.len());
+                let mut data = Vec::with_capacity(rows.len()*columns);
+                for (row, inner) in rows.iter().enumerate() {
+                    if inner.len() != columns {
+                        return Err(WorldError::RowLengthMismatch { name: stringify!($name), row, expected: columns, actual: inner.len() });
+                    }
+                    data.extend_from_slice(inner);
+                }
+                Self::from_vec(rows.len(), columns, data)
+            }
+
+            pub fn frames(&self) -> usize {
+                self.frames
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.frames == 0
+            }
+
+            pub fn columns(&self) -> usize {
+                self.columns
+            }
+
+            pub fn row(&self, frame: usize) -> &[f64] {
+                &self.data[frame*self.columns..(frame+1)*self.columns]
+            }
+
+            pub fn row_mut(&mut self, frame: usize) -> &mut [f64] {
+                &mut self.data[frame*self.columns..(frame+1)*self.columns]
+            }
+
+            pub fn rows(&self) -> ChunksExact<'_, f64> {
+                self.data.chunks_exact(self.columns)
+            }
+
+            pub fn rows_mut(&mut self) -> ChunksExactMut<'_, f64> {
+                self.data.chunks_exact_mut(self.columns)
+            }
+
+            pub fn as_slice(&self) -> &[f64] {
+                &self.data
+            }
+
+            pub fn as_mut_slice(&mut self) -> &mut [f64] {
+                &mut self.data
+            }
+
+            pub fn into_vec(self) -> Vec<f64> {
+                self.data
+            }
+
+            pub fn to_rows(&self) -> Vec<Vec<f64>> {
+                self.rows().map(|inner| inner.to_vec()).collect()
+            }
+
+            // Not every matrix type is handed to WORLD.
+            #[allow(dead_code)]
+            pub(crate) fn row_ptrs(&self) -> Vec<*const f64> {
+                self.rows().map(|inner| inner.as_ptr()).collect()
+            }
+
+            #[allow(dead_code)]
+            pub(crate) fn row_ptrs_mut(&mut self) -> Vec<*mut f64> {
+                self.rows_mut().map(|inner| inner.as_mut_ptr()).collect()
+            }
+        }
+
+        impl Index<(usize, usize)> for $name {
+            type Output = f64;
+
+            fn index(&self, (frame, column): (usize, usize)) -> &f64 {
+                assert!(column < self.columns, "column {} out of range for {} columns", column, self.columns);
+                &self.data[frame*self.columns+column]
+            }
+        }
+
+        impl IndexMut<(usize, usize)> for $name {
+            fn index_mut(&mut self, (frame, column): (usize, usize)) -> &mut f64 {
+                assert!(column < self.columns, "column {} out of range for {} columns", column, self.columns);
+                &mut self.data[frame*self.columns+column]
+            }
+        }
+    };
+}
+
+macro_rules! spectral {
+    ($name:ident) => {
+        impl $name {
+            pub fn with_fft_size(frames: usize, fft_size: usize) -> Self {
+                Self::zeros(frames, fft_size/2+1)
+            }
+
+            pub fn bins(&self) -> usize {
+                self.columns
+            }
+
+            /// 0 for a matrix with a single bin.
+            pub fn fft_size(&self) -> usize {
+                (self.columns-1)*2
+            }
+
+            pub fn bin_to_hz(&self, bin: usize, fs: i32) -> Result<f64> {
+                self.check_bins()?;
+                Ok(bin as f64 * fs as f64 / self.fft_size() as f64)
+            }
+
+            pub fn hz_to_bin(&self, hz: f64, fs: i32) -> Result<usize> {
+                self.check_bins()?;
+                let bin = (hz * self.fft_size() as f64 / fs as f64).round();
+                Ok((bin.max(0.0) as usize).min(self.columns-1))
+            }
+
+            // A frequency axis needs DC and Nyquist bins.
+            fn check_bins(&self) -> Result<()> {
+                if self.columns < 2 {
+                    return Err(WorldError::InvalidDimensions(self.columns as i32));
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+macro_rules! coded {
+    ($name:ident) => {
+        impl $name {
+            pub fn dimensions(&self) -> usize {
+                self.columns
+            }
+        }
+    };
+}
+
+matrix!(
+    /// Spectral envelope, one row of `fft_size/2+1` bins per frame.
+    Spectrogram
+);
+matrix!(
+    /// Band aperiodicity, one row of `fft_size/2+1` bins per frame.
+    Aperiodicity
+);
+matrix!(
+    /// Output of `code_spectral_envelope`, one row of `number_of_dimensions` per frame.
+    CodedSpectrogram
+);
+matrix!(
+    /// Output of `code_aperiodicity`, one row of `get_number_of_aperiodicities(fs)` per frame.
+    CodedAperiodicity
+);
+
+matrix!(
+    /// Mel-cepstrum or mel-generalized cepstrum, one row of `order+1` coefficients per frame.
+    MelCepstrum
+);
+
+matrix!(
+    /// Mel filterbank energies, one row of `bands` per frame.
+    MelSpectrogram
+);
+matrix!(
+    /// Mel-frequency cepstral coefficients, one row per frame.
+    Mfcc
+);
+
+spectral!(Spectrogram);
+spectral!(Aperiodicity);
+coded!(CodedSpectrogram);
+coded!(CodedAperiodicity);
+coded!(MelCepstrum);
+coded!(Mfcc);
+
+impl MelSpectrogram {
+    pub fn bands(&self) -> usize {
+        self.columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Spectrogram, Aperiodicity, WorldError};
+
+    #[test]
+    fn test_with_fft_size() {
+        let spectrogram = Spectrogram::with_fft_size(3, 2048);
+        assert_eq!(spectrogram.frames(),   3);
+        assert_eq!(spectrogram.bins(),     1025);
+        assert_eq!(spectrogram.fft_size(), 2048);
+        assert_eq!(spectrogram.as_slice().len(), 3*1025);
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let aperiodicity = Aperiodicity::from_rows(&[vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]).unwrap();
+        assert_eq!(aperiodicity.row(1), &[4.0, 5.0, 6.0]);
+        assert_eq!(aperiodicity[(0, 2)], 3.0);
+        assert_eq!(aperiodicity.to_rows(), vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+        assert_eq!(Aperiodicity::from_rows(&[vec![1.0, 2.0, 3.0], vec![4.0]]),
+                   Err(WorldError::RowLengthMismatch { name: "Aperiodicity", row: 1, expected: 3, actual: 1 }));
+    }
+
+    #[test]
+    fn test_from_vec() {
+        assert!(Spectrogram::from_vec(2, 3, vec![0.0; 6]).is_ok());
+        assert_eq!(Spectrogram::from_vec(2, 3, vec![0.0; 5]),
+                   Err(WorldError::LengthMismatch { name: "Spectrogram", expected: 6, actual: 5 }));
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut spectrogram = Spectrogram::with_fft_size(2, 8);
+        spectrogram[(1, 4)] = 1.0;
+        spectrogram.row_mut(0)[0] = 2.0;
+        assert_eq!(spectrogram.as_slice(), &[2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_bin_to_hz() {
+        let spectrogram = Spectrogram::with_fft_size(1, 2048);
+        assert_eq!(spectrogram.bin_to_hz(1024, 44100), Ok(22050.0));
+        assert_eq!(spectrogram.hz_to_bin(22050.0, 44100), Ok(1024));
+        assert_eq!(spectrogram.hz_to_bin(1e6, 44100), Ok(1024));
+    }
+
+    #[test]
+    fn test_single_bin() {
+        let spectrogram = Spectrogram::zeros(2, 1);
+        assert_eq!(spectrogram.fft_size(), 0);
+        assert_eq!(spectrogram.rows().count(), 2);
+        assert_eq!(spectrogram.hz_to_bin(100.0, 16000), Err(WorldError::InvalidDimensions(1)));
+        assert_eq!(spectrogram.bin_to_hz(0, 16000), Err(WorldError::InvalidDimensions(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Spectrogram needs at least one column")]
+    fn test_zeros_without_columns() {
+        Spectrogram::zeros(2, 0);
+    }
+
+    #[test]
+    fn test_without_columns() {
+        assert_eq!(Spectrogram::from_vec(2, 0, Vec::new()), Err(WorldError::InvalidDimensions(0)));
+        assert_eq!(Spectrogram::from_rows(&[Vec::new(), Vec::new()]), Err(WorldError::InvalidDimensions(0)));
+    }
+}
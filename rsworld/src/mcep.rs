@@ -0,0 +1,259 @@
+use std::f64::consts::PI;
+
+use crate::{Spectrogram, MelCepstrum};
+use crate::fft::{Complex, rfft, irfft};
+use crate::error::{WorldError, Result, check_fs, check_fft_size, check_length, check_positive};
+
+fn check_alpha(alpha: f64) -> Result<()> {
+    if !(alpha.is_finite() && alpha.abs() < 1.0) {
+        return Err(WorldError::InvalidOption { name: "alpha", value: alpha });
+    }
+    Ok(())
+}
+
+fn check_gamma(gamma: f64) -> Result<()> {
+    if !(gamma.is_finite() && (-1.0..=1.0).contains(&gamma)) {
+        return Err(WorldError::InvalidOption { name: "gamma", value: gamma });
+    }
+    Ok(())
+}
+
+/// All-pass constant whose warped frequency axis is closest to the mel scale at `fs`,
+/// searched in steps of 0.001 like SPTK/pysptk's `mcepalpha` (0.41 at 16 kHz, 0.544 at 44.1 kHz).
+pub fn mcep_alpha(fs: i32) -> Result<f64> {
+    check_fs(fs)?;
+    const POINTS: usize = 1000;
+    let step = fs as f64 / 2.0 / POINTS as f64;
+    let mel: Vec<f64> = (0..POINTS).map(|i| (1.0 + step * i as f64 / 1000.0).ln()).collect();
+    let mel_max       = mel[POINTS-1];
+    let distance = |alpha: f64| {
+        let warp = |i: usize| {
+            let omega = PI / POINTS as f64 * i as f64;
+            ((1.0 - alpha*alpha) * omega.sin()).atan2((1.0 + alpha*alpha) * omega.cos() - 2.0*alpha)
+        };
+        let warp_max = warp(POINTS-1);
+        (0..POINTS).map(|i| (mel[i] / mel_max - warp(i) / warp_max).powi(2)).sum::<f64>()
+    };
+    let best = (0..1000).min_by(|&a, &b| distance(a as f64 / 1000.0).total_cmp(&distance(b as f64 / 1000.0))).unwrap_or(0);
+    Ok(best as f64 / 1000.0)
+}
+
+// SPTK freqt: recursive all-pass frequency warping of c1 into m2+1 coefficients.
+fn freqt(c1: &[f64], m2: usize, alpha: f64) -> Vec<f64> {
+    let b     = 1.0 - alpha*alpha;
+    let mut g = vec![0.0; m2+1];
+    let mut d = vec![0.0; m2+1];
+    for &c in c1.iter().rev() {
+        d.copy_from_slice(&g);
+        g[0] = c + alpha * d[0];
+        if m2 >= 1 {
+            g[1] = b * d[0] + alpha * d[1];
+        }
+        for j in 2..=m2 {
+            g[j] = d[j-1] + alpha * (d[j] - g[j-1]);
+        }
+    }
+    g
+}
+
+// SPTK gnorm/ignorm: between the generalized cepstrum and its gain-normalised form.
+fn gnorm(c: &mut [f64], gamma: f64) {
+    if gamma == 0.0 {
+        c[0] = c[0].exp();
+        return;
+    }
+    let k = 1.0 + gamma * c[0];
+    c[1..].iter_mut().for_each(|v| *v /= k);
+    c[0] = k.powf(1.0 / gamma);
+}
+
+fn ignorm(c: &mut [f64], gamma: f64) {
+    if gamma == 0.0 {
+        c[0] = c[0].ln();
+        return;
+    }
+    let k = c[0].powf(gamma);
+    c[1..].iter_mut().for_each(|v| *v *= k);
+    c[0] = (k - 1.0) / gamma;
+}
+
+// SPTK gc2gc on normalised cepstra.
+fn gc2gc(c1: &[f64], gamma1: f64, m2: usize, gamma2: f64) -> Vec<f64> {
+    let m1     = c1.len() - 1;
+    let mut c2 = vec![0.0; m2+1];
+    c2[0] = c1[0];
+    for i in 1..=m2 {
+        let (mut ss1, mut ss2) = (0.0, 0.0);
+        for (k, c) in c1.iter().enumerate().take(m1.min(i-1)+1).skip(1) {
+            let mk = i - k;
+            let cc = c * c2[mk];
+            ss2 += k as f64 * cc;
+            ss1 += mk as f64 * cc;
+        }
+        let c = if i <= m1 { c1[i] } else { 0.0 };
+        c2[i] = c + (gamma2 * ss2 - gamma1 * ss1) / i as f64;
+    }
+    c2
+}
+
+// SPTK mgc2mgc.
+fn mgc2mgc(c1: &[f64], alpha1: f64, gamma1: f64, m2: usize, alpha2: f64, gamma2: f64) -> Vec<f64> {
+    let mut c2 = if alpha1 == alpha2 {
+        let mut c1 = c1.to_vec();
+        gnorm(&mut c1, gamma1);
+        gc2gc(&c1, gamma1, m2, gamma2)
+    } else {
+        let mut c2 = freqt(c1, m2, (alpha2 - alpha1) / (1.0 - alpha1 * alpha2));
+        gnorm(&mut c2, gamma1);
+        gc2gc(&c2, gamma1, m2, gamma2)
+    };
+    ignorm(&mut c2, gamma2);
+    c2
+}
+
+/// Mel-generalized cepstrum of order `order` from a CheapTrick power spectrogram.
+///
+/// `gamma` 0 gives the mel-cepstrum, -1 the mel-LPC form; `alpha` 0 leaves the
+/// frequency axis linear. The steps are those of pysptk's `sp2mc`: the whole
+/// cepstrum of the log power spectrum with c0 halved, warped by SPTK's `freqt`,
+/// then converted with `mgc2mgc` when `gamma` is not 0. Every bin must be
+/// positive and finite.
+pub fn sp_to_mgc(spectrogram: &Spectrogram, order: usize, alpha: f64, gamma: f64) -> Result<MelCepstrum> {
+    check_alpha(alpha)?;
+    check_gamma(gamma)?;
+    if spectrogram.is_empty() {
+        return Err(WorldError::EmptySpectrogram);
+    }
+    let fft_size = spectrogram.fft_size();
+    check_fft_size(check_length("spectrogram", fft_size)?)?;
+    check_positive("spectrogram", spectrogram.as_slice())?;
+    let mut data = Vec::with_capacity(spectrogram.frames()*(order+1));
+    for row in spectrogram.rows() {
+        let log_spectrum: Vec<Complex> = row.iter().map(|&v| Complex::new(v.ln(), 0.0)).collect();
+        let mut c = irfft(&log_spectrum, fft_size);
+        c[0] /= 2.0;
+        data.extend(mgc2mgc(&c, 0.0, 0.0, order, alpha, gamma));
+    }
+    MelCepstrum::from_vec(spectrogram.frames(), order+1, data)
+}
+
+/// Inverse of `sp_to_mgc`, giving a power spectrogram `synthesis` accepts.
+/// Follows pysptk's `mc2sp` after converting back to `gamma` 0.
+pub fn mgc_to_sp(mgc: &MelCepstrum, alpha: f64, gamma: f64, fft_size: i32) -> Result<Spectrogram> {
+    check_alpha(alpha)?;
+    check_gamma(gamma)?;
+    check_fft_size(fft_size)?;
+    let fft_size        = fft_size as usize;
+    let mut spectrogram = Spectrogram::with_fft_size(mgc.frames(), fft_size);
+    for (frame, row) in mgc.rows().enumerate() {
+        let mut c = mgc2mgc(row, alpha, gamma, fft_size/2, 0.0, 0.0);
+        c[0] *= 2.0;
+        let mut symmetric = c.clone();
+        symmetric.extend(c[1..fft_size/2].iter().rev());
+        for (out, v) in spectrogram.row_mut(frame).iter_mut().zip(rfft(&symmetric)) {
+            *out = v.re.exp();
+        }
+    }
+    Ok(spectrogram)
+}
+
+/// Mel-cepstrum, the `gamma = 0` case of `sp_to_mgc`.
+pub fn sp_to_mcep(spectrogram: &Spectrogram, order: usize, alpha: f64) -> Result<MelCepstrum> {
+    sp_to_mgc(spectrogram, order, alpha, 0.0)
+}
+
+pub fn mcep_to_sp(mcep: &MelCepstrum, alpha: f64, fft_size: i32) -> Result<Spectrogram> {
+    mgc_to_sp(mcep, alpha, 0.0, fft_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Spectrogram, MelCepstrum, WorldError, mcep_alpha, sp_to_mcep, mcep_to_sp, sp_to_mgc, mgc_to_sp};
+
+    fn envelope(frames: usize, fft_size: usize) -> Spectrogram {
+        let mut spectrogram = Spectrogram::with_fft_size(frames, fft_size);
+        for (frame, row) in spectrogram.rows_mut().enumerate() {
+            for (bin, v) in row.iter_mut().enumerate() {
+                let x = bin as f64 / (fft_size/2) as f64;
+                *v = 1e-3 * (-(4.0 + frame as f64) * x).exp() * (1.0 + 0.5 * (6.0 * x).cos());
+            }
+        }
+        spectrogram
+    }
+
+    #[test]
+    fn test_mcep_alpha() {
+        assert_eq!(mcep_alpha(16000), Ok(0.41));
+        assert_eq!(mcep_alpha(22050), Ok(0.455));
+        assert_eq!(mcep_alpha(44100), Ok(0.544));
+        assert_eq!(mcep_alpha(48000), Ok(0.554));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let spectrogram = envelope(3, 1024);
+        let alpha       = mcep_alpha(16000).unwrap();
+        for &gamma in &[0.0, -0.5, -1.0] {
+            let mgc = sp_to_mgc(&spectrogram, 59, alpha, gamma).unwrap();
+            assert_eq!((mgc.frames(), mgc.dimensions()), (3, 60));
+            let reconstructed = mgc_to_sp(&mgc, alpha, gamma, 1024).unwrap();
+            for (v, reference) in reconstructed.as_slice().iter().zip(spectrogram.as_slice()) {
+                assert!((v / reference).ln().abs() < 0.05, "gamma {}: {} != {}", gamma, v, reference);
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_cepstrum() {
+        // With alpha 0 and full order the conversion is exact.
+        let spectrogram   = envelope(1, 64);
+        let mcep          = sp_to_mcep(&spectrogram, 32, 0.0).unwrap();
+        let reconstructed = mcep_to_sp(&mcep, 0.0, 64).unwrap();
+        for (v, reference) in reconstructed.as_slice().iter().zip(spectrogram.as_slice()) {
+            assert!((v / reference - 1.0).abs() < 1e-9);
+        }
+        // c0 is the mean log amplitude.
+        let mean = spectrogram.row(0).iter().enumerate().map(|(bin, v)| {
+            let weight = if bin == 0 || bin == 32 { 0.5 } else { 1.0 };
+            weight * v.ln()
+        }).sum::<f64>() / 64.0;
+        assert!((mcep[(0, 0)] - mean).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_warped_one_pole() {
+        // The spectrum of 1/(1 - a z^-1) is warped into (1 + alpha z^-1)/((1 - a alpha)(1 - b z^-1))
+        // with b = (a - alpha)/(1 - a alpha), whose cepstrum is known in closed form.
+        let (a, alpha, fft_size) = (0.5, 0.41, 1024);
+        let mut spectrogram = Spectrogram::with_fft_size(1, fft_size);
+        for (bin, v) in spectrogram.row_mut(0).iter_mut().enumerate() {
+            let omega = 2.0 * std::f64::consts::PI * bin as f64 / fft_size as f64;
+            *v = 1.0 / (1.0 - 2.0 * a * omega.cos() + a * a);
+        }
+        let mcep = sp_to_mcep(&spectrogram, 24, alpha).unwrap();
+        let b    = (a - alpha) / (1.0 - a * alpha);
+        assert!((mcep[(0, 0)] + (1.0 - a * alpha).ln()).abs() < 1e-12);
+        for n in 1..=24 {
+            let expected = (b.powi(n as i32) - (-alpha).powi(n as i32)) / n as f64;
+            assert!((mcep[(0, n)] - expected).abs() < 1e-12, "c{}: {} != {}", n, mcep[(0, n)], expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let spectrogram = envelope(1, 64);
+        assert_eq!(sp_to_mcep(&spectrogram, 24, 1.0),
+                   Err(WorldError::InvalidOption { name: "alpha", value: 1.0 }));
+        assert_eq!(sp_to_mgc(&spectrogram, 24, 0.4, 2.0),
+                   Err(WorldError::InvalidOption { name: "gamma", value: 2.0 }));
+        assert_eq!(mcep_to_sp(&MelCepstrum::zeros(1, 25), 0.4, 63),
+                   Err(WorldError::InvalidFftSize(63)));
+        let mut spectrogram = envelope(2, 64);
+        spectrogram[(1, 3)] = 0.0;
+        assert_eq!(sp_to_mcep(&spectrogram, 24, 0.4),
+                   Err(WorldError::NonPositiveValue { name: "spectrogram", index: 33+3 }));
+        spectrogram[(0, 5)] = f64::NAN;
+        assert_eq!(sp_to_mcep(&spectrogram, 24, 0.4),
+                   Err(WorldError::NonFiniteSample { name: "spectrogram", index: 5 }));
+    }
+}
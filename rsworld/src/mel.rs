@@ -0,0 +1,236 @@
+use std::f64::consts::PI;
+
+use crate::{Spectrogram, MelSpectrogram, Mfcc};
+use crate::fft::rfft;
+use crate::error::{WorldError, Result, check_fs, check_fft_size, check_length, check_finite, check_signal};
+
+/// How filterbank energies are compressed before they are returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    /// Natural log of the energy, clamped below at the given floor.
+    Log(f64),
+    /// `10 log10` of the energy, clamped below at the given floor.
+    Decibel(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MelOption {
+    pub bands:       usize,
+    pub f_min:       f64,
+    /// Upper edge of the filterbank; `None` is the Nyquist frequency.
+    pub f_max:       Option<f64>,
+    pub compression: Compression,
+}
+
+impl Default for MelOption {
+    fn default() -> Self {
+        MelOption { bands: 80, f_min: 0.0, f_max: None, compression: Compression::Log(1e-10) }
+    }
+}
+
+fn hz_to_mel(hz: f64) -> f64 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f64) -> f64 {
+    700.0 * (10f64.powf(mel / 2595.0) - 1.0)
+}
+
+impl MelOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bands(mut self, bands: usize) -> Self {
+        self.bands = bands;
+        self
+    }
+
+    pub fn f_min(mut self, f_min: f64) -> Self {
+        self.f_min = f_min;
+        self
+    }
+
+    pub fn f_max(mut self, f_max: f64) -> Self {
+        self.f_max = Some(f_max);
+        self
+    }
+
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn validate(&self, fs: i32) -> Result<()> {
+        check_fs(fs)?;
+        if self.bands == 0 {
+            return Err(WorldError::InvalidOption { name: "bands", value: 0.0 });
+        }
+        if !(self.f_min.is_finite() && self.f_min >= 0.0) {
+            return Err(WorldError::InvalidOption { name: "f_min", value: self.f_min });
+        }
+        let f_max = self.f_max.unwrap_or(fs as f64 / 2.0);
+        if !(f_max > self.f_min && f_max <= fs as f64 / 2.0) {
+            return Err(WorldError::InvalidOption { name: "f_max", value: f_max });
+        }
+        if let Compression::Log(floor) | Compression::Decibel(floor) = self.compression {
+            if !(floor.is_finite() && floor > 0.0) {
+                return Err(WorldError::InvalidOption { name: "floor", value: floor });
+            }
+        }
+        Ok(())
+    }
+
+    // HTK-style triangular filters, peak 1, with edges evenly spaced on the mel scale.
+    // One row of `bins` weights per band.
+    fn filterbank(&self, fs: i32, fft_size: usize) -> Vec<Vec<f64>> {
+        let bins     = fft_size/2+1;
+        let f_max    = self.f_max.unwrap_or(fs as f64 / 2.0);
+        let (lo, hi) = (hz_to_mel(self.f_min), hz_to_mel(f_max));
+        let edges: Vec<f64> = (0..self.bands+2).map(|i| mel_to_hz(lo + (hi - lo) * i as f64 / (self.bands+1) as f64)).collect();
+        edges.windows(3).map(|edge| {
+            (0..bins).map(|bin| {
+                let hz      = bin as f64 * fs as f64 / fft_size as f64;
+                let rising  = (hz - edge[0]) / (edge[1] - edge[0]);
+                let falling = (edge[2] - hz) / (edge[2] - edge[1]);
+                rising.min(falling).max(0.0)
+            }).collect()
+        }).collect()
+    }
+
+    fn compress(&self, energy: f64) -> f64 {
+        match self.compression {
+            Compression::None           => energy,
+            Compression::Log(floor)     => energy.max(floor).ln(),
+            Compression::Decibel(floor) => 10.0 * energy.max(floor).log10(),
+        }
+    }
+}
+
+/// Mel filterbank energies of a power spectrogram such as `cheaptrick` returns,
+/// one row of `option.bands` per frame.
+pub fn mel_spectrogram(spectrogram: &Spectrogram, fs: i32, option: &MelOption) -> Result<MelSpectrogram> {
+    option.validate(fs)?;
+    if spectrogram.is_empty() {
+        return Err(WorldError::EmptySpectrogram);
+    }
+    // The filters are laid out on the bins' frequencies, which need DC and Nyquist.
+    if spectrogram.bins() < 2 {
+        return Err(WorldError::InvalidDimensions(spectrogram.bins() as i32));
+    }
+    check_finite("spectrogram", spectrogram.as_slice())?;
+    let filterbank = option.filterbank(fs, spectrogram.fft_size());
+    let mut mel    = MelSpectrogram::zeros(spectrogram.frames(), option.bands);
+    for (row, out) in spectrogram.rows().zip(mel.rows_mut()) {
+        for (filter, v) in filterbank.iter().zip(out) {
+            *v = option.compress(filter.iter().zip(row).map(|(w, p)| w * p).sum());
+        }
+    }
+    Ok(mel)
+}
+
+/// Same as `mel_spectrogram`, but from the power spectrum of `x` under a Hann
+/// window of `fft_size` samples centred on each of `temporal_positions` (seconds),
+/// so the frames line up with the `dio`/`harvest` F0.
+pub fn mel_spectrogram_from_signal(x: &[f64], fs: i32, temporal_positions: &[f64], fft_size: i32, option: &MelOption) -> Result<MelSpectrogram> {
+    check_signal(x)?;
+    check_fs(fs)?;
+    check_fft_size(fft_size)?;
+    check_finite("temporal_positions", temporal_positions)?;
+    check_length("temporal_positions", temporal_positions.len())?;
+    let fft_size        = fft_size as usize;
+    let window          = (0..fft_size).map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / fft_size as f64).cos()).collect::<Vec<f64>>();
+    let mut spectrogram = Spectrogram::with_fft_size(temporal_positions.len(), fft_size);
+    let mut frame       = vec![0.0; fft_size];
+    for (position, row) in temporal_positions.iter().zip(spectrogram.rows_mut()) {
+        let start = (position * fs as f64).round() as isize - (fft_size/2) as isize;
+        for (i, (out, w)) in frame.iter_mut().zip(&window).enumerate() {
+            let index = start + i as isize;
+            *out = if index >= 0 && (index as usize) < x.len() { x[index as usize] * w } else { 0.0 };
+        }
+        for (out, v) in row.iter_mut().zip(rfft(&frame)) {
+            *out = v.re * v.re + v.im * v.im;
+        }
+    }
+    mel_spectrogram(&spectrogram, fs, option)
+}
+
+/// The first `coefficients` terms of the orthonormal DCT-II of each row, which
+/// are MFCCs when the mel spectrogram was log compressed.
+pub fn mfcc(mel_spectrogram: &MelSpectrogram, coefficients: usize) -> Result<Mfcc> {
+    let bands = mel_spectrogram.bands();
+    if coefficients == 0 || coefficients > bands {
+        return Err(WorldError::InvalidOption { name: "coefficients", value: coefficients as f64 });
+    }
+    let mut mfcc = Mfcc::zeros(mel_spectrogram.frames(), coefficients);
+    for (row, out) in mel_spectrogram.rows().zip(mfcc.rows_mut()) {
+        for (k, c) in out.iter_mut().enumerate() {
+            let scale = if k == 0 { (1.0 / bands as f64).sqrt() } else { (2.0 / bands as f64).sqrt() };
+            *c = scale * row.iter().enumerate().map(|(m, v)| v * (PI * k as f64 * (m as f64 + 0.5) / bands as f64).cos()).sum::<f64>();
+        }
+    }
+    Ok(mfcc)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Spectrogram, MelSpectrogram, WorldError, Compression, MelOption, DioOption, dio, mel_spectrogram, mel_spectrogram_from_signal, mfcc};
+
+    fn peak(row: &[f64]) -> usize {
+        (0..row.len()).max_by(|&a, &b| row[a].total_cmp(&row[b])).unwrap()
+    }
+
+    #[test]
+    fn test_mel_spectrogram() {
+        let fs              = 16000;
+        let mut spectrogram = Spectrogram::with_fft_size(2, 1024);
+        spectrogram.row_mut(0)[64]  = 1.0;
+        spectrogram.row_mut(1)[320] = 1.0;
+        let option = MelOption::new().bands(40).compression(Compression::None);
+        let mel    = mel_spectrogram(&spectrogram, fs, &option).unwrap();
+        assert_eq!((mel.frames(), mel.bands()), (2, 40));
+        // 1 kHz lands lower on the mel axis than 5 kHz, and each tone reaches a peak weight near 1.
+        assert!(peak(mel.row(0)) < peak(mel.row(1)));
+        assert!(mel.row(0)[peak(mel.row(0))] > 0.5);
+        assert!((mel.row(0).iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let log = mel_spectrogram(&spectrogram, fs, &option.compression(Compression::Decibel(1e-10))).unwrap();
+        assert_eq!(log.row(0)[39], -100.0);
+    }
+
+    #[test]
+    fn test_from_signal() {
+        let fs = 16000;
+        let x: Vec<f64> = (0..8000).map(|i| (2.0 * std::f64::consts::PI * 1000.0 * i as f64 / fs as f64).sin()).collect();
+        let (temporal_positions, f0) = dio(&x, fs, &DioOption::new()).unwrap();
+        let option = MelOption::new().bands(40);
+        let mel    = mel_spectrogram_from_signal(&x, fs, &temporal_positions, 1024, &option).unwrap();
+        assert_eq!(mel.frames(), f0.len());
+
+        let mut spectrogram = Spectrogram::with_fft_size(1, 1024);
+        spectrogram.row_mut(0)[64] = 1.0;
+        let reference = mel_spectrogram(&spectrogram, fs, &option).unwrap();
+        assert_eq!(peak(mel.row(mel.frames()/2)), peak(reference.row(0)));
+    }
+
+    #[test]
+    fn test_mfcc() {
+        let mel  = MelSpectrogram::from_vec(1, 4, vec![2.0; 4]).unwrap();
+        let mfcc = mfcc(&mel, 3).unwrap();
+        assert!((mfcc[(0, 0)] - 4.0).abs() < 1e-12);
+        assert!(mfcc.row(0)[1..].iter().all(|c| c.abs() < 1e-12));
+        assert_eq!(super::mfcc(&mel, 5), Err(WorldError::InvalidOption { name: "coefficients", value: 5.0 }));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let spectrogram = Spectrogram::with_fft_size(1, 1024);
+        assert_eq!(mel_spectrogram(&spectrogram, 16000, &MelOption::new().f_max(9000.0)),
+                   Err(WorldError::InvalidOption { name: "f_max", value: 9000.0 }));
+        assert_eq!(mel_spectrogram(&spectrogram, 16000, &MelOption::new().bands(0)),
+                   Err(WorldError::InvalidOption { name: "bands", value: 0.0 }));
+        assert_eq!(mel_spectrogram(&Spectrogram::zeros(1, 1), 16000, &MelOption::new()),
+                   Err(WorldError::InvalidDimensions(1)));
+    }
+}
@@ -0,0 +1,195 @@
+use std::f64::consts::LN_10;
+
+use crate::{Spectrogram, MelCepstrum, mcep_alpha, sp_to_mcep};
+use crate::error::{WorldError, Result, check_same_length, check_finite, check_positive};
+
+/// How frames of a reference and a target of different length are paired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// Both sequences must have the same number of frames.
+    #[default]
+    Exact,
+    /// Frames are paired by index and the longer sequence is cut.
+    Truncate,
+    /// The target is stretched linearly onto the reference's frames.
+    Stretch,
+}
+
+impl Alignment {
+    pub(crate) fn pairs(self, reference: usize, target: usize) -> Result<Vec<(usize, usize)>> {
+        match self {
+            Alignment::Exact => {
+                check_same_length("target", reference, target)?;
+                Ok((0..reference).map(|i| (i, i)).collect())
+            },
+            Alignment::Truncate => Ok((0..reference.min(target)).map(|i| (i, i)).collect()),
+            Alignment::Stretch => {
+                if target == 0 {
+                    return Ok(Vec::new());
+                }
+                let scale = if reference > 1 { (target-1) as f64 / (reference-1) as f64 } else { 0.0 };
+                Ok((0..reference).map(|i| (i, (i as f64 * scale).round() as usize)).collect())
+            },
+        }
+    }
+}
+
+fn check_pairs(pairs: &[(usize, usize)]) -> Result<()> {
+    if pairs.is_empty() {
+        return Err(WorldError::EmptySpectrogram);
+    }
+    Ok(())
+}
+
+fn check_contours(reference: &[f64], target: &[f64]) -> Result<()> {
+    check_finite("reference", reference)?;
+    check_finite("target", target)
+}
+
+// Log-F0 pairs of the frames voiced in both contours.
+fn voiced_pairs(reference: &[f64], target: &[f64], alignment: Alignment) -> Result<Vec<(f64, f64)>> {
+    check_contours(reference, target)?;
+    let pairs: Vec<(f64, f64)> = alignment.pairs(reference.len(), target.len())?.into_iter()
+        .filter(|&(r, t)| reference[r] > 0.0 && target[t] > 0.0)
+        .map(|(r, t)| (reference[r].ln(), target[t].ln()))
+        .collect();
+    if pairs.is_empty() {
+        return Err(WorldError::NoVoicedFrames);
+    }
+    Ok(pairs)
+}
+
+/// Mel-cepstral distortion in dB, averaged over frames. `c0` (energy) is left out
+/// as is customary, so at least two dimensions are needed.
+pub fn mel_cepstral_distortion(reference: &MelCepstrum, target: &MelCepstrum, alignment: Alignment) -> Result<f64> {
+    check_same_length("target dimensions", reference.dimensions(), target.dimensions())?;
+    if reference.dimensions() < 2 {
+        return Err(WorldError::InvalidDimensions(reference.dimensions() as i32));
+    }
+    let pairs = alignment.pairs(reference.frames(), target.frames())?;
+    check_pairs(&pairs)?;
+    let total: f64 = pairs.iter().map(|&(r, t)| {
+        let squared: f64 = reference.row(r)[1..].iter().zip(&target.row(t)[1..]).map(|(a, b)| (a - b).powi(2)).sum();
+        10.0 / LN_10 * (2.0 * squared).sqrt()
+    }).sum();
+    Ok(total / pairs.len() as f64)
+}
+
+/// `mel_cepstral_distortion` of two CheapTrick spectrograms, converted with
+/// `sp_to_mcep` at `order` and the `mcep_alpha` for `fs`.
+pub fn mel_cepstral_distortion_from_spectrogram(reference: &Spectrogram, target: &Spectrogram, fs: i32, order: usize, alignment: Alignment) -> Result<f64> {
+    let alpha = mcep_alpha(fs)?;
+    mel_cepstral_distortion(&sp_to_mcep(reference, order, alpha)?, &sp_to_mcep(target, order, alpha)?, alignment)
+}
+
+/// Root mean square F0 error in cents over the frames voiced in both contours.
+pub fn f0_rmse_cents(reference: &[f64], target: &[f64], alignment: Alignment) -> Result<f64> {
+    let pairs = voiced_pairs(reference, target, alignment)?;
+    let cents = 1200.0 / 2f64.ln();
+    Ok((pairs.iter().map(|(r, t)| (cents * (t - r)).powi(2)).sum::<f64>() / pairs.len() as f64).sqrt())
+}
+
+/// Fraction of frames whose voicing decision differs, 0.0 to 1.0.
+pub fn vuv_error_rate(reference: &[f64], target: &[f64], alignment: Alignment) -> Result<f64> {
+    check_contours(reference, target)?;
+    let pairs = alignment.pairs(reference.len(), target.len())?;
+    check_pairs(&pairs)?;
+    let errors = pairs.iter().filter(|&&(r, t)| (reference[r] > 0.0) != (target[t] > 0.0)).count();
+    Ok(errors as f64 / pairs.len() as f64)
+}
+
+/// Pearson correlation of log-F0 over the frames voiced in both contours. It is
+/// undefined, and an error, when either contour is constant over those frames.
+pub fn log_f0_correlation(reference: &[f64], target: &[f64], alignment: Alignment) -> Result<f64> {
+    let pairs    = voiced_pairs(reference, target, alignment)?;
+    if pairs.iter().all(|&(r, _)| r == pairs[0].0) {
+        return Err(WorldError::ZeroVariance { name: "reference" });
+    }
+    if pairs.iter().all(|&(_, t)| t == pairs[0].1) {
+        return Err(WorldError::ZeroVariance { name: "target" });
+    }
+    let n        = pairs.len() as f64;
+    let (mr, mt) = pairs.iter().fold((0.0, 0.0), |(a, b), (r, t)| (a + r / n, b + t / n));
+    let (covariance, vr, vt) = pairs.iter().fold((0.0, 0.0, 0.0), |(c, a, b), (r, t)| {
+        (c + (r - mr) * (t - mt), a + (r - mr).powi(2), b + (t - mt).powi(2))
+    });
+    Ok(covariance / (vr * vt).sqrt())
+}
+
+/// Log-spectral distance in dB: the RMS over bins of the power ratio in dB,
+/// averaged over frames. Every bin must be positive and finite.
+pub fn log_spectral_distance(reference: &Spectrogram, target: &Spectrogram, alignment: Alignment) -> Result<f64> {
+    check_same_length("target bins", reference.bins(), target.bins())?;
+    check_positive("reference", reference.as_slice())?;
+    check_positive("target", target.as_slice())?;
+    let pairs = alignment.pairs(reference.frames(), target.frames())?;
+    check_pairs(&pairs)?;
+    let total: f64 = pairs.iter().map(|&(r, t)| {
+        let row = reference.row(r);
+        let squared: f64 = row.iter().zip(target.row(t)).map(|(a, b)| (10.0 * (a / b).log10()).powi(2)).sum();
+        (squared / row.len() as f64).sqrt()
+    }).sum();
+    Ok(total / pairs.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Spectrogram, MelCepstrum, WorldError, Alignment, mel_cepstral_distortion, mel_cepstral_distortion_from_spectrogram,
+                f0_rmse_cents, vuv_error_rate, log_f0_correlation, log_spectral_distance};
+
+    #[test]
+    fn test_mel_cepstral_distortion() {
+        let reference = MelCepstrum::from_vec(2, 3, vec![1.0, 0.0, 0.0, 5.0, 0.5, 0.5]).unwrap();
+        let target    = MelCepstrum::from_vec(2, 3, vec![9.0, 0.1, 0.0, 0.0, 0.5, 0.5]).unwrap();
+        let expected  = 10.0 / std::f64::consts::LN_10 * (2.0 * 0.01f64).sqrt() / 2.0;
+        assert!((mel_cepstral_distortion(&reference, &target, Alignment::Exact).unwrap() - expected).abs() < 1e-12);
+
+        let mut spectrogram = Spectrogram::with_fft_size(3, 1024);
+        spectrogram.as_mut_slice().iter_mut().enumerate().for_each(|(i, v)| *v = 1e-3 * (1.0 + (i % 7) as f64));
+        assert!(mel_cepstral_distortion_from_spectrogram(&spectrogram, &spectrogram, 16000, 24, Alignment::Exact).unwrap().abs() < 1e-9);
+
+        let energy = MelCepstrum::from_vec(2, 1, vec![1.0, 2.0]).unwrap();
+        assert_eq!(mel_cepstral_distortion(&energy, &energy, Alignment::Exact), Err(WorldError::InvalidDimensions(1)));
+    }
+
+    #[test]
+    fn test_f0_metrics() {
+        let reference = [0.0, 100.0, 200.0, 400.0, 0.0];
+        let target    = [0.0, 200.0, 200.0, 0.0,   0.0];
+        // Only frames 1 and 2 are voiced in both: errors of 1200 and 0 cents.
+        assert!((f0_rmse_cents(&reference, &target, Alignment::Exact).unwrap() - 1200.0 / 2f64.sqrt()).abs() < 1e-9);
+        assert_eq!(vuv_error_rate(&reference, &target, Alignment::Exact), Ok(0.2));
+
+        let scaled: Vec<f64> = reference.iter().map(|v| v * 1.5).collect();
+        assert!((log_f0_correlation(&reference, &scaled, Alignment::Exact).unwrap() - 1.0).abs() < 1e-12);
+        assert_eq!(f0_rmse_cents(&[0.0, 100.0], &[100.0, 0.0], Alignment::Exact), Err(WorldError::NoVoicedFrames));
+        assert_eq!(log_f0_correlation(&[0.0, 100.0, 100.0], &[0.0, 100.0, 120.0], Alignment::Exact),
+                   Err(WorldError::ZeroVariance { name: "reference" }));
+        assert_eq!(log_f0_correlation(&reference, &[0.0, 150.0, 150.0, 150.0, 0.0], Alignment::Exact),
+                   Err(WorldError::ZeroVariance { name: "target" }));
+    }
+
+    #[test]
+    fn test_log_spectral_distance() {
+        let reference = Spectrogram::from_vec(1, 3, vec![1.0, 1.0, 1.0]).unwrap();
+        let target    = Spectrogram::from_vec(1, 3, vec![10.0, 10.0, 10.0]).unwrap();
+        assert!((log_spectral_distance(&reference, &target, Alignment::Exact).unwrap() - 10.0).abs() < 1e-12);
+        let silent = Spectrogram::from_vec(1, 3, vec![1.0, 0.0, 1.0]).unwrap();
+        assert_eq!(log_spectral_distance(&reference, &silent, Alignment::Exact),
+                   Err(WorldError::NonPositiveValue { name: "target", index: 1 }));
+        let infinite = Spectrogram::from_vec(1, 3, vec![1.0, 1.0, f64::INFINITY]).unwrap();
+        assert_eq!(log_spectral_distance(&infinite, &target, Alignment::Exact),
+                   Err(WorldError::NonFiniteSample { name: "reference", index: 2 }));
+    }
+
+    #[test]
+    fn test_alignment() {
+        let reference = [100.0, 110.0, 120.0];
+        let target    = [100.0, 105.0, 110.0, 115.0, 120.0];
+        assert_eq!(vuv_error_rate(&reference, &target, Alignment::Exact),
+                   Err(WorldError::LengthMismatch { name: "target", expected: 3, actual: 5 }));
+        assert_eq!(Alignment::Truncate.pairs(3, 5), Ok(vec![(0, 0), (1, 1), (2, 2)]));
+        assert_eq!(Alignment::Stretch.pairs(3, 5), Ok(vec![(0, 0), (1, 2), (2, 4)]));
+        assert_eq!(f0_rmse_cents(&reference, &target, Alignment::Stretch), Ok(0.0));
+    }
+}
@@ -0,0 +1,143 @@
+use std::f64::consts::PI;
+
+use crate::{CheapTrickOption, Spectrogram, check_f0};
+use crate::fft::Complex;
+use crate::error::{Result, check_signal, check_finite};
+use super::common::{SAFE_GUARD_MINIMUM, EPS, matlab_round, Randn, dc_correction, linear_smoothing, forward_real_fft, inverse_real_fft};
+
+const DEFAULT_F0: f64 = 500.0;
+
+// The signal around `current_position` under a Hann window three periods long,
+// normalised to unit energy and with its weighted mean removed.
+fn windowed_waveform(x: &[f64], fs: i32, current_f0: f64, current_position: f64, fft_size: usize, randn: &mut Randn) -> Vec<f64> {
+    let half_window_length = matlab_round(1.5 * fs as f64 / current_f0) as isize;
+    let origin             = matlab_round(current_position * fs as f64 + 0.001) as isize;
+    let mut window: Vec<f64> = (-half_window_length..=half_window_length)
+        .map(|i| 0.5 * (PI * (i as f64 / 1.5 / fs as f64) * current_f0).cos() + 0.5)
+        .collect();
+    let average = window.iter().map(|w| w * w).sum::<f64>().sqrt();
+    window.iter_mut().for_each(|w| *w /= average);
+
+    let mut waveform = vec![0.0; fft_size];
+    for (i, (out, w)) in waveform.iter_mut().zip(&window).enumerate() {
+        let index = (origin - half_window_length + i as isize).clamp(0, x.len() as isize - 1) as usize;
+        *out = x[index] * w + randn.next() * SAFE_GUARD_MINIMUM;
+    }
+    let weighting_coefficient = waveform.iter().sum::<f64>() / window.iter().sum::<f64>();
+    for (out, w) in waveform.iter_mut().zip(&window) {
+        *out -= w * weighting_coefficient;
+    }
+    waveform
+}
+
+// Smoothing on the log axis and spectral recovery, both as lifters in the cepstrum.
+fn smoothing_with_recovery(power_spectrum: &[f64], f0: f64, fs: i32, fft_size: usize, q1: f64) -> Vec<f64> {
+    let half             = fft_size/2;
+    let mut log_spectrum = vec![0.0; fft_size];
+    for (out, v) in log_spectrum.iter_mut().zip(power_spectrum) {
+        *out = v.ln();
+    }
+    for i in 1..half {
+        log_spectrum[fft_size-i] = log_spectrum[i];
+    }
+    let cepstrum = forward_real_fft(&log_spectrum);
+    let liftered: Vec<Complex> = cepstrum.iter().enumerate().map(|(i, c)| {
+        let (smoothing, compensation) = if i == 0 {
+            (1.0, (1.0 - 2.0 * q1) + 2.0 * q1)
+        } else {
+            let quefrency = i as f64 / fs as f64;
+            ((PI * f0 * quefrency).sin() / (PI * f0 * quefrency), (1.0 - 2.0 * q1) + 2.0 * q1 * (2.0 * PI * quefrency * f0).cos())
+        };
+        Complex::new(c.re * smoothing * compensation / fft_size as f64, 0.0)
+    }).collect();
+    inverse_real_fft(&liftered, fft_size)[..=half].iter().map(|v| v.exp()).collect()
+}
+
+fn cheaptrick_general_body(x: &[f64], fs: i32, current_f0: f64, fft_size: usize, current_position: f64, q1: f64, randn: &mut Randn) -> Vec<f64> {
+    let waveform = windowed_waveform(x, fs, current_f0, current_position, fft_size, randn);
+    let mut power_spectrum: Vec<f64> = forward_real_fft(&waveform).iter().map(|v| v.re * v.re + v.im * v.im).collect();
+    dc_correction(&mut power_spectrum, current_f0, fs, fft_size);
+    let mut smoothed = linear_smoothing(&power_spectrum, current_f0 * 2.0 / 3.0, fs, fft_size);
+    // A safeguard against zeros in the spectrum.
+    smoothed.iter_mut().for_each(|v| *v += randn.next().abs() * EPS);
+    smoothing_with_recovery(&smoothed, current_f0, fs, fft_size, q1)
+}
+
+// Frames with F0 at or below the lowest F0 `fft_size` can analyse use 500 Hz.
+fn analysis_f0(f0: f64, fs: i32, fft_size: usize) -> f64 {
+    if f0 <= 3.0 * fs as f64 / (fft_size as f64 - 3.0) { DEFAULT_F0 } else { f0 }
+}
+
+/// Same as `crate::get_fft_size_for_cheaptrick`, without calling into WORLD.
+pub fn get_fft_size_for_cheaptrick(fs: i32, option: &CheapTrickOption) -> Result<i32> {
+    option.fft_size(fs)
+}
+
+/// Pure-Rust CheapTrick with the same interface as `crate::cheaptrick`. Bins
+/// agree with the C implementation to a relative 1e-9, except in valleys more
+/// than 50 dB below the frame's peak, where FFT rounding leaves them within
+/// 1e-14 of the peak.
+pub fn cheaptrick(x: &[f64], fs: i32, temporal_positions: &[f64], f0: &[f64], option: &CheapTrickOption) -> Result<Spectrogram> {
+    let fft_size = get_fft_size_for_cheaptrick(fs, option)? as usize;
+    check_signal(x)?;
+    check_f0(temporal_positions, f0)?;
+    // WORLD reseeds its noise generator once per call, so it runs on across frames.
+    let mut randn       = Randn::new();
+    let mut spectrogram = Spectrogram::with_fft_size(f0.len(), fft_size);
+    for ((row, &position), &f0) in spectrogram.rows_mut().zip(temporal_positions).zip(f0) {
+        row.copy_from_slice(&cheaptrick_general_body(x, fs, analysis_f0(f0, fs, fft_size), fft_size, position, option.q1, &mut randn));
+    }
+    Ok(spectrogram)
+}
+
+/// The spectral envelope of one frame, `fft_size/2+1` bins. Frames are
+/// independent of each other, so they can be computed as they arrive or in
+/// parallel. The result is `cheaptrick` of this frame alone; within a longer
+/// call only the safeguard noise WORLD adds (1e-12 in the waveform, 2e-16 in
+/// the power spectrum) differs.
+pub fn cheaptrick_frame(x: &[f64], fs: i32, temporal_position: f64, f0: f64, option: &CheapTrickOption) -> Result<Vec<f64>> {
+    let fft_size = get_fft_size_for_cheaptrick(fs, option)? as usize;
+    check_signal(x)?;
+    check_finite("temporal_positions", &[temporal_position])?;
+    check_finite("f0", &[f0])?;
+    Ok(cheaptrick_general_body(x, fs, analysis_f0(f0, fs, fft_size), fft_size, temporal_position, option.q1, &mut Randn::new()))
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use crate::{CheapTrickOption, HarvestOption, cheaptrick, harvest, get_fft_size_for_cheaptrick};
+    use crate::native::test_signals::{vibrato, peak};
+
+    // Relative 1e-9, loosened to 1e-14 of the peak in valleys far below it.
+    fn assert_close(values: &[f64], reference: &[f64], frame: usize) {
+        let peak = peak(reference);
+        crate::native::test_signals::assert_close(&format!("frame {}", frame), values, reference, |r| 1e-9 * r + 1e-14 * peak);
+    }
+
+    #[test]
+    fn test_matches_c() {
+        for &(fs, option) in &[(16000, CheapTrickOption::new()), (44100, CheapTrickOption::new().q1(-0.3).f0_floor(100.0))] {
+            assert_eq!(super::get_fft_size_for_cheaptrick(fs, &option), get_fft_size_for_cheaptrick(fs, &option));
+            let x = vibrato(fs, fs as usize);
+            let (temporal_positions, f0) = harvest(&x, fs, &HarvestOption::new()).unwrap();
+            let reference   = cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+            let spectrogram = super::cheaptrick(&x, fs, &temporal_positions, &f0, &option).unwrap();
+            assert_eq!(spectrogram.fft_size(), reference.fft_size());
+            for frame in 0..f0.len() {
+                assert_close(spectrogram.row(frame), reference.row(frame), frame);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frame() {
+        let fs = 16000;
+        let x  = vibrato(fs, fs as usize);
+        let option    = CheapTrickOption::new();
+        let reference = cheaptrick(&x, fs, &[0.5], &[150.0], &option).unwrap();
+        assert_close(&super::cheaptrick_frame(&x, fs, 0.5, 150.0, &option).unwrap(), reference.row(0), 0);
+        // Unvoiced frames are analysed at 500 Hz.
+        let unvoiced = cheaptrick(&x, fs, &[0.5], &[500.0], &option).unwrap();
+        assert_close(&super::cheaptrick_frame(&x, fs, 0.5, 0.0, &option).unwrap(), unvoiced.row(0), 0);
+    }
+}
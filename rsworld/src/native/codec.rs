@@ -0,0 +1,194 @@
+use std::f64::consts::PI;
+
+use crate::{Spectrogram, Aperiodicity, CodedSpectrogram, CodedAperiodicity, WorldError, check_aperiodicities};
+use crate::fft::{Complex, transform};
+use crate::error::{Result, check_fs, check_fft_size, check_length, check_same_length};
+use super::common::{SAFE_GUARD_MINIMUM, interp1, interp1q};
+
+const FREQUENCY_INTERVAL: f64 = 3000.0;
+const UPPER_LIMIT: f64        = 15000.0;
+const FLOOR_FREQUENCY: f64    = 40.0;
+const CEIL_FREQUENCY: f64     = 20000.0;
+const M0: f64                 = 1127.01048;
+const F0: f64                 = 700.0;
+
+fn frequency_to_mel(frequency: f64) -> f64 {
+    M0 * (frequency / F0 + 1.0).ln()
+}
+
+fn mel_to_frequency(mel: f64) -> f64 {
+    F0 * ((mel / M0).exp() - 1.0)
+}
+
+/// Same as `crate::get_number_of_aperiodicities`, without calling into WORLD.
+pub fn get_number_of_aperiodicities(fs: i32) -> Result<i32> {
+    check_fs(fs)?;
+    Ok((UPPER_LIMIT.min(fs as f64 / 2.0 - FREQUENCY_INTERVAL) / FREQUENCY_INTERVAL) as i32)
+}
+
+/// Pure-Rust `crate::code_aperiodicity`: the aperiodicity in dB at every
+/// multiple of 3 kHz. Agrees with the C implementation to 1e-9 dB.
+pub fn code_aperiodicity(aperiodicity: &Aperiodicity, fs: i32) -> Result<CodedAperiodicity> {
+    check_fs(fs)?;
+    check_length("aperiodicity", aperiodicity.frames())?;
+    let fft_size = check_length("aperiodicity", aperiodicity.fft_size())?;
+    check_fft_size(fft_size)?;
+    let number_of_aperiodicities = check_aperiodicities(get_number_of_aperiodicities(fs)?)?;
+    let coarse_frequency_axis: Vec<f64> = (0..number_of_aperiodicities).map(|i| FREQUENCY_INTERVAL * (i as f64 + 1.0)).collect();
+    let mut coded_aperiodicity = CodedAperiodicity::zeros(aperiodicity.frames(), number_of_aperiodicities);
+    for (i, row) in coded_aperiodicity.rows_mut().enumerate() {
+        let log_aperiodicity: Vec<f64> = aperiodicity.row(i).iter().map(|v| 20.0 * v.log10()).collect();
+        row.copy_from_slice(&interp1q(0.0, fs as f64 / fft_size as f64, &log_aperiodicity, &coarse_frequency_axis));
+    }
+    Ok(coded_aperiodicity)
+}
+
+/// Pure-Rust `crate::decode_aperiodicity`. Frames whose mean coded value is
+/// above -0.5 dB decode as fully aperiodic. Agrees with the C implementation
+/// to a relative 1e-9.
+pub fn decode_aperiodicity(coded_aperiodicity: &CodedAperiodicity, fs: i32, fft_size: i32) -> Result<Aperiodicity> {
+    check_fs(fs)?;
+    check_fft_size(fft_size)?;
+    check_length("coded_aperiodicity", coded_aperiodicity.frames())?;
+    let number_of_aperiodicities = check_aperiodicities(get_number_of_aperiodicities(fs)?)?;
+    check_same_length("coded_aperiodicity dimensions", number_of_aperiodicities, coded_aperiodicity.dimensions())?;
+    let fft_size = fft_size as usize;
+    let frequency_axis: Vec<f64> = (0..=fft_size/2).map(|i| fs as f64 / fft_size as f64 * i as f64).collect();
+    let mut coarse_frequency_axis: Vec<f64> = (0..=number_of_aperiodicities).map(|i| i as f64 * FREQUENCY_INTERVAL).collect();
+    coarse_frequency_axis.push(fs as f64 / 2.0);
+
+    let mut aperiodicity = Aperiodicity::with_fft_size(coded_aperiodicity.frames(), fft_size);
+    aperiodicity.as_mut_slice().iter_mut().for_each(|v| *v = 1.0 - SAFE_GUARD_MINIMUM);
+    for (i, row) in aperiodicity.rows_mut().enumerate() {
+        let coded = coded_aperiodicity.row(i);
+        if coded.iter().sum::<f64>() / number_of_aperiodicities as f64 > -0.5 {
+            continue;
+        }
+        let mut coarse_aperiodicity = vec![-60.0];
+        coarse_aperiodicity.extend_from_slice(coded);
+        coarse_aperiodicity.push(-SAFE_GUARD_MINIMUM);
+        for (out, v) in row.iter_mut().zip(interp1(&coarse_frequency_axis, &coarse_aperiodicity, &frequency_axis)) {
+            *out = 10f64.powf(v / 20.0);
+        }
+    }
+    Ok(aperiodicity)
+}
+
+/// Pure-Rust `crate::code_spectral_envelope`: a DCT of the log spectral
+/// envelope on a mel axis from 40 Hz to `min(fs/2, 20 kHz)`. Coefficients
+/// agree with the C implementation to 1e-9. At most `fft_size/2` are
+/// meaningful; any beyond are zero.
+pub fn code_spectral_envelope(spectrogram: &Spectrogram, fs: i32, number_of_dimensions: i32) -> Result<CodedSpectrogram> {
+    check_fs(fs)?;
+    if number_of_dimensions <= 0 {
+        return Err(WorldError::InvalidDimensions(number_of_dimensions));
+    }
+    check_length("spectrogram", spectrogram.frames())?;
+    let fft_size = check_length("spectrogram", spectrogram.fft_size())?;
+    check_fft_size(fft_size)?;
+    let fft_size      = fft_size as usize;
+    let max_dimension = fft_size/2;
+    let floor_mel     = frequency_to_mel(FLOOR_FREQUENCY);
+    let ceil_mel      = frequency_to_mel(CEIL_FREQUENCY.min(fs as f64 / 2.0));
+    let mel_axis: Vec<f64>       = (0..max_dimension).map(|i| (ceil_mel - floor_mel) * i as f64 / max_dimension as f64 + floor_mel).collect();
+    let frequency_axis: Vec<f64> = (0..=max_dimension).map(|i| frequency_to_mel(i as f64 * fs as f64 / fft_size as f64)).collect();
+    let weight: Vec<Complex> = (0..max_dimension).map(|i| {
+        let scale = if i == 0 { 2.0 / 2f64.sqrt() } else { 2.0 };
+        Complex::new(scale * (i as f64 * PI / fft_size as f64).cos() / (fft_size as f64).sqrt(),
+                     2.0 * (i as f64 * PI / fft_size as f64).sin() / (fft_size as f64).sqrt())
+    }).collect();
+
+    let mut coded_spectral_envelope = CodedSpectrogram::zeros(spectrogram.frames(), number_of_dimensions as usize);
+    for (i, row) in coded_spectral_envelope.rows_mut().enumerate() {
+        let log_spectrum: Vec<f64> = spectrogram.row(i).iter().map(|v| v.ln()).collect();
+        let mel_spectrum = interp1(&frequency_axis, &log_spectrum, &mel_axis);
+        // DCT through an FFT of the even samples followed by the odd ones reversed.
+        let mut buffer = vec![Complex::default(); max_dimension];
+        for j in 0..max_dimension/2 {
+            buffer[j]                   = Complex::new(mel_spectrum[j*2], 0.0);
+            buffer[j + max_dimension/2] = Complex::new(mel_spectrum[max_dimension-j*2-1], 0.0);
+        }
+        transform(&mut buffer, -1.0);
+        let normalization = (max_dimension as f64).sqrt();
+        for (out, (b, w)) in row.iter_mut().zip(buffer.iter().zip(&weight)) {
+            *out = (b.re * w.re - b.im * w.im) / normalization;
+        }
+    }
+    Ok(coded_spectral_envelope)
+}
+
+/// Pure-Rust `crate::decode_spectral_envelope`. Agrees with the C
+/// implementation to a relative 1e-9. Coefficients beyond `fft_size/2` are
+/// ignored.
+pub fn decode_spectral_envelope(coded_spectrogram: &CodedSpectrogram, fs: i32, fft_size: i32) -> Result<Spectrogram> {
+    check_fs(fs)?;
+    check_fft_size(fft_size)?;
+    check_length("coded_spectrogram", coded_spectrogram.frames())?;
+    check_length("coded_spectrogram", coded_spectrogram.dimensions())?;
+    let fft_size      = fft_size as usize;
+    let max_dimension = fft_size/2;
+    let floor_mel     = frequency_to_mel(FLOOR_FREQUENCY);
+    let ceil_mel      = frequency_to_mel(CEIL_FREQUENCY.min(fs as f64 / 2.0));
+    let mut mel_axis  = vec![0.0];
+    mel_axis.extend((0..max_dimension).map(|i| mel_to_frequency((ceil_mel - floor_mel) * i as f64 / max_dimension as f64 + floor_mel)));
+    mel_axis.push(fs as f64 / 2.0);
+    let frequency_axis: Vec<f64> = (0..=max_dimension).map(|i| i as f64 * fs as f64 / fft_size as f64).collect();
+    let weight: Vec<Complex> = (0..max_dimension).map(|i| {
+        let scale = if i == 0 { 1.0 / 2f64.sqrt() } else { 1.0 };
+        Complex::new(scale * (i as f64 * PI / fft_size as f64).cos() * (fft_size as f64).sqrt(),
+                     (i as f64 * PI / fft_size as f64).sin() * (fft_size as f64).sqrt())
+    }).collect();
+
+    let mut spectrogram = Spectrogram::with_fft_size(coded_spectrogram.frames(), fft_size);
+    for (i, row) in spectrogram.rows_mut().enumerate() {
+        let normalization = (max_dimension as f64).sqrt();
+        let mut buffer    = vec![Complex::default(); max_dimension];
+        for (out, (c, w)) in buffer.iter_mut().zip(coded_spectrogram.row(i).iter().zip(&weight)) {
+            *out = Complex::new(c * w.re * normalization, c * w.im * normalization);
+        }
+        transform(&mut buffer, 1.0);
+        // Undo the sample reordering of the DCT and pad both ends of the mel axis.
+        let mut mel_spectrum = vec![0.0; max_dimension + 2];
+        for j in 0..max_dimension/2 {
+            mel_spectrum[j*2+1] = buffer[j].re;
+            mel_spectrum[j*2+2] = buffer[max_dimension-j-1].re;
+        }
+        mel_spectrum[0]               = mel_spectrum[1];
+        mel_spectrum[max_dimension+1] = mel_spectrum[max_dimension];
+        for (out, v) in row.iter_mut().zip(interp1(&mel_axis, &mel_spectrum, &frequency_axis)) {
+            *out = (v / max_dimension as f64).exp();
+        }
+    }
+    Ok(spectrogram)
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use crate::{code_aperiodicity, decode_aperiodicity, code_spectral_envelope, decode_spectral_envelope, get_number_of_aperiodicities};
+    use crate::native::test_signals::{vibrato, analyse, assert_close};
+
+    #[test]
+    fn test_aperiodicity_matches_c() {
+        for &fs in &[16000, 44100] {
+            assert_eq!(super::get_number_of_aperiodicities(fs), get_number_of_aperiodicities(fs));
+            let (_, spectrogram, aperiodicity) = analyse(&vibrato(fs, fs as usize), fs);
+            let fft_size = spectrogram.fft_size() as i32;
+            let coded    = code_aperiodicity(&aperiodicity, fs).unwrap();
+            assert_close("coded", super::code_aperiodicity(&aperiodicity, fs).unwrap().as_slice(), coded.as_slice(), |_| 1e-9);
+            assert_close("decoded", super::decode_aperiodicity(&coded, fs, fft_size).unwrap().as_slice(),
+                         decode_aperiodicity(&coded, fs, fft_size).unwrap().as_slice(), |r| 1e-9 * r);
+        }
+    }
+
+    #[test]
+    fn test_spectral_envelope_matches_c() {
+        for &(fs, dimensions) in &[(16000, 40), (44100, 60)] {
+            let (_, spectrogram, _) = analyse(&vibrato(fs, fs as usize), fs);
+            let fft_size = spectrogram.fft_size() as i32;
+            let coded    = code_spectral_envelope(&spectrogram, fs, dimensions).unwrap();
+            assert_close("coded", super::code_spectral_envelope(&spectrogram, fs, dimensions).unwrap().as_slice(), coded.as_slice(), |_| 1e-9);
+            assert_close("decoded", super::decode_spectral_envelope(&coded, fs, fft_size).unwrap().as_slice(),
+                         decode_spectral_envelope(&coded, fs, fft_size).unwrap().as_slice(), |r| 1e-9 * r);
+        }
+    }
+}
@@ -0,0 +1,251 @@
+// Ports of WORLD's common.cpp and matlabfunctions.cpp helpers, plus FFT
+// wrappers that follow the conventions of WORLD's fft.cpp.
+
+use std::f64::consts::PI;
+
+use crate::fft::{Complex, transform};
+
+pub(crate) const SAFE_GUARD_MINIMUM: f64 = 0.000000000001;
+pub(crate) const EPS: f64                = f64::EPSILON;
+
+pub(crate) fn matlab_round(x: f64) -> i32 {
+    if x > 0.0 { (x + 0.5) as i32 } else { (x - 0.5) as i32 }
+}
+
+pub(crate) fn get_suitable_fft_size(sample: usize) -> usize {
+    2f64.powf(((sample as f64).ln() / 2f64.ln()) as i32 as f64 + 1.0) as usize
+}
+
+pub(crate) fn nuttall_window(length: usize) -> Vec<f64> {
+    (0..length).map(|i| {
+        let t = i as f64 / (length as f64 - 1.0);
+        0.355768 - 0.487396 * (2.0 * PI * t).cos() + 0.144232 * (4.0 * PI * t).cos() - 0.012604 * (6.0 * PI * t).cos()
+    }).collect()
+}
+
+// MATLAB histc as WORLD uses it: for each (ascending) edge, the 1-based index of
+// the last x not above it, clamped to 1..x.len()-1.
+fn histc(x: &[f64], edges: &[f64]) -> Vec<usize> {
+    let mut index = vec![1; edges.len()];
+    let mut count = 1;
+    for (i, &edge) in edges.iter().enumerate() {
+        if edge < x[0] {
+            continue;
+        }
+        while count < x.len() && edge >= x[count] {
+            count += 1;
+        }
+        index[i] = count.min(x.len()-1);
+    }
+    index
+}
+
+/// Linear interpolation of (x, y) at xi with linear extrapolation outside x, as
+/// WORLD's `interp1`.
+pub(crate) fn interp1(x: &[f64], y: &[f64], xi: &[f64]) -> Vec<f64> {
+    histc(x, xi).iter().zip(xi).map(|(&k, &xi)| {
+        let s = (xi - x[k-1]) / (x[k] - x[k-1]);
+        y[k-1] + s * (y[k] - y[k-1])
+    }).collect()
+}
+
+/// MATLAB's `interp1q` on a regular grid starting at `x` with step `shift`, as
+/// WORLD's `interp1Q`: no extrapolation beyond the last sample.
+pub(crate) fn interp1q(x: f64, shift: f64, y: &[f64], xi: &[f64]) -> Vec<f64> {
+    let mut delta_y: Vec<f64> = y.windows(2).map(|w| w[1] - w[0]).collect();
+    delta_y.push(0.0);
+    xi.iter().map(|&xi| {
+        let base     = ((xi - x) / shift) as i32 as usize;
+        let fraction = (xi - x) / shift - base as f64;
+        y[base] + delta_y[base] * fraction
+    }).collect()
+}
+
+/// Folds the part of `power_spectrum` below `f0` back onto itself, as WORLD's
+/// `DCCorrection`.
+pub(crate) fn dc_correction(power_spectrum: &mut [f64], f0: f64, fs: i32, fft_size: usize) {
+    let upper_limit = 2 + (f0 * fft_size as f64 / fs as f64) as usize;
+    let axis: Vec<f64> = (0..upper_limit).map(|i| i as f64 * fs as f64 / fft_size as f64).collect();
+    let replica = interp1q(f0 - axis[0], -(fs as f64) / fft_size as f64, &power_spectrum[..=upper_limit], &axis[..upper_limit-1]);
+    for (v, r) in power_spectrum.iter_mut().zip(replica) {
+        *v += r;
+    }
+}
+
+/// Moving average of `width` Hz over the `fft_size/2+1` bins of `input`, with
+/// the spectrum mirrored at both ends, as WORLD's `LinearSmoothing`.
+pub(crate) fn linear_smoothing(input: &[f64], width: f64, fs: i32, fft_size: usize) -> Vec<f64> {
+    let half     = fft_size/2;
+    let boundary = (width * fft_size as f64 / fs as f64) as usize + 1;
+    let mut mirroring = Vec::with_capacity(half + boundary*2 + 1);
+    mirroring.extend((0..boundary).map(|i| input[boundary-i]));
+    mirroring.extend_from_slice(&input[..half]);
+    mirroring.extend((0..=boundary).map(|i| input[half-i]));
+    let mut segment = Vec::with_capacity(mirroring.len());
+    let mut sum     = 0.0;
+    for (i, &v) in mirroring.iter().enumerate() {
+        sum = if i == 0 { v * fs as f64 / fft_size as f64 } else { v * fs as f64 / fft_size as f64 + sum };
+        segment.push(sum);
+    }
+    let mut axis: Vec<f64> = (0..=half).map(|i| i as f64 / fft_size as f64 * fs as f64 - width / 2.0).collect();
+    let origin   = -(boundary as f64 - 0.5) * fs as f64 / fft_size as f64;
+    let interval = fs as f64 / fft_size as f64;
+    let low      = interp1q(origin, interval, &segment, &axis);
+    axis.iter_mut().for_each(|v| *v += width);
+    let high = interp1q(origin, interval, &segment, &axis);
+    high.iter().zip(&low).map(|(h, l)| (h - l) / width).collect()
+}
+
+/// WORLD's `randn`: the sum of twelve xorshift draws, shifted to zero mean. A
+/// new generator starts where `randn_reseed` leaves WORLD's global one.
+pub(crate) struct Randn {
+    state: [u32; 4],
+}
+
+impl Randn {
+    pub(crate) fn new() -> Self {
+        Randn::with_seed(0)
+    }
+
+    /// A generator whose sequence depends on `seed`; seed 0 is WORLD's own.
+    /// Only the first two words of the state are mixed, so it is never all zero.
+    pub(crate) fn with_seed(seed: u64) -> Self {
+        Randn { state: [123456789 ^ seed as u32, 362436069 ^ (seed >> 32) as u32, 521288629, 88675123] }
+    }
+
+    fn step(&mut self) -> u32 {
+        let [x, y, z, w] = self.state;
+        let t = x ^ (x << 11);
+        let w = (w ^ (w >> 19)) ^ (t ^ (t >> 8));
+        self.state = [y, z, self.state[3], w];
+        w >> 4
+    }
+
+    pub(crate) fn next(&mut self) -> f64 {
+        let sum = (0..12).map(|_| self.step()).sum::<u32>();
+        sum as f64 / 268435456.0 - 6.0
+    }
+}
+
+// Third order IIR low-pass used by `decimate`, coefficients per ratio copied
+// verbatim from WORLD.
+#[allow(clippy::excessive_precision)]
+fn filter_for_decimate(x: &[f64], r: usize) -> Vec<f64> {
+    let (a, b): ([f64; 3], [f64; 2]) = match r {
+        11 => ([2.450743295230728, -2.06794904601978, 0.59574774438332101], [0.0026822508007163792, 0.0080467524021491377]),
+        12 => ([2.4981398605924205, -2.1368928194784025, 0.62187513816221485], [0.0021097275904709001, 0.0063291827714127002]),
+        10 => ([2.3936475118069387, -1.9873904075111861, 0.5658879979027055], [0.0034818622251927556, 0.010445586675578267]),
+        9  => ([2.3236003491759578, -1.8921545617463598, 0.53148928133729068], [0.0046331164041389372, 0.013899349212416812]),
+        8  => ([2.2357462340187593, -1.7780899984041358, 0.49152555365968692], [0.0063522763407111993, 0.019056829022133598]),
+        7  => ([2.1225239019534703, -1.6395144861046302, 0.44469707800587366], [0.0090366882681608418, 0.027110064804482525]),
+        6  => ([1.9715352749512141, -1.4686795689225347, 0.3893908434965701], [0.013469181309343825, 0.040407543928031475]),
+        5  => ([1.7610939654280557, -1.2554914843859768, 0.3237186507788215], [0.021334858522387423, 0.06400457556716227]),
+        4  => ([1.4499664446880227, -0.98943497080950582, 0.24578252340690215], [0.036710750339322612, 0.11013225101796784]),
+        3  => ([0.95039378983237421, -0.67429146741526791, 0.15412211621346475], [0.071221945171178636, 0.21366583551353591]),
+        2  => ([0.041156734567757189, -0.42599112459189636, 0.041037215479961225], [0.16797464681802227, 0.50392394045406674]),
+        _  => ([0.0; 3], [0.0; 2]),
+    };
+    let mut w = [0.0; 3];
+    x.iter().map(|&v| {
+        let wt = v + a[0] * w[0] + a[1] * w[1] + a[2] * w[2];
+        let y  = b[0] * wt + b[1] * w[0] + b[1] * w[1] + b[0] * w[2];
+        w = [wt, w[0], w[1]];
+        y
+    }).collect()
+}
+
+/// Zero-phase low-pass and downsampling by `r`, as WORLD's `decimate`.
+pub(crate) fn decimate(x: &[f64], r: usize) -> Vec<f64> {
+    const NFACT: usize = 9;
+    let length     = x.len();
+    let mut padded = Vec::with_capacity(length + 2*NFACT);
+    padded.extend((0..NFACT).map(|i| 2.0 * x[0] - x[NFACT-i]));
+    padded.extend_from_slice(x);
+    padded.extend((0..NFACT).map(|i| 2.0 * x[length-1] - x[length-2-i]));
+    let mut filtered = filter_for_decimate(&padded, r);
+    filtered.reverse();
+    let mut filtered = filter_for_decimate(&filtered, r);
+    filtered.reverse();
+    let nout = (length-1) / r + 1;
+    let nbeg = r + length - r * nout;
+    (nbeg..length+NFACT).step_by(r).map(|i| filtered[i+NFACT-1]).collect()
+}
+
+/// Convolves the signal behind `y_spectrum` with `filter` (`fft_size` samples)
+/// and returns the unnormalised inverse, as DIO and Harvest do. WORLD mirrors
+/// each product to bin `fft_size-i-1`, which only reaches the bins the inverse
+/// transform reads for the last two; kept for parity.
+pub(crate) fn convolve(y_spectrum: &[Complex], filter: &[f64]) -> Vec<f64> {
+    let fft_size     = filter.len();
+    let mut spectrum = forward_real_fft(filter);
+    spectrum.resize(fft_size, Complex::default());
+    spectrum[0] = y_spectrum[0] * spectrum[0];
+    for i in 1..=fft_size/2 {
+        spectrum[i] = y_spectrum[i] * spectrum[i];
+        spectrum[fft_size-i-1] = spectrum[i];
+    }
+    inverse_real_fft(&spectrum[..=fft_size/2], fft_size)
+}
+
+// Intervals between negative-going zero crossings, and where they are (seconds).
+fn zero_crossing_engine(signal: &[f64], fs: f64) -> (Vec<f64>, Vec<f64>) {
+    let edges: Vec<usize> = (1..signal.len()).filter(|&i| 0.0 < signal[i-1] && signal[i] <= 0.0).collect();
+    if edges.len() < 2 {
+        return (Vec::new(), Vec::new());
+    }
+    let fine: Vec<f64> = edges.iter().map(|&e| e as f64 - signal[e-1] / (signal[e] - signal[e-1])).collect();
+    fine.windows(2).map(|w| ((w[0] + w[1]) / 2.0 / fs, fs / (w[1] - w[0]))).unzip()
+}
+
+/// Locations and rates of the negative-going and positive-going zero crossings,
+/// peaks and dips of `signal`, in that order.
+pub(crate) fn zero_crossing_intervals(mut signal: Vec<f64>, fs: f64) -> [(Vec<f64>, Vec<f64>); 4] {
+    // The differences are one sample shorter; an empty signal has none.
+    let length   = signal.len().saturating_sub(1);
+    let negative = zero_crossing_engine(&signal, fs);
+    signal.iter_mut().for_each(|v| *v = -*v);
+    let positive = zero_crossing_engine(&signal, fs);
+    for i in 0..length {
+        signal[i] -= signal[i+1];
+    }
+    let peak = zero_crossing_engine(&signal[..length], fs);
+    signal[..length].iter_mut().for_each(|v| *v = -*v);
+    let dip = zero_crossing_engine(&signal[..length], fs);
+    [negative, positive, peak, dip]
+}
+
+/// `fft_plan_dft_r2c_1d`: the `n/2+1` bins of the forward transform.
+pub(crate) fn forward_real_fft(x: &[f64]) -> Vec<Complex> {
+    crate::fft::rfft(x)
+}
+
+/// `fft_plan_dft_c2r_1d`: unnormalised inverse from the first `n/2+1` bins.
+pub(crate) fn inverse_real_fft(spectrum: &[Complex], n: usize) -> Vec<f64> {
+    let mut buffer = vec![Complex::default(); n];
+    buffer[0]   = Complex::new(spectrum[0].re, 0.0);
+    buffer[n/2] = Complex::new(spectrum[n/2].re, 0.0);
+    for k in 1..n/2 {
+        buffer[k]   = spectrum[k];
+        buffer[n-k] = spectrum[k].conj();
+    }
+    transform(&mut buffer, 1.0);
+    buffer.iter().map(|v| v.re).collect()
+}
+
+/// Minimum phase spectrum, `fft_size/2+1` bins, whose log amplitude is
+/// `log_spectrum` (`fft_size/2+1` bins), as WORLD's `GetMinimumPhaseSpectrum`.
+pub(crate) fn minimum_phase_spectrum(log_spectrum: &[f64], fft_size: usize) -> Vec<Complex> {
+    let half             = fft_size/2;
+    let mut mirrored     = vec![0.0; fft_size];
+    mirrored[..=half].copy_from_slice(&log_spectrum[..=half]);
+    for i in half+1..fft_size {
+        mirrored[i] = log_spectrum[fft_size-i];
+    }
+    // Fold the cepstrum onto positive quefrencies.
+    let mut cepstrum = vec![Complex::default(); fft_size];
+    for (i, c) in forward_real_fft(&mirrored).iter().enumerate() {
+        cepstrum[i] = if i == 0 || i == half { *c } else { Complex::new(c.re * 2.0, c.im * 2.0) };
+    }
+    transform(&mut cepstrum, -1.0);
+    cepstrum[..=half].iter().map(|v| Complex::from_polar((v.re / fft_size as f64).exp(), v.im / fft_size as f64)).collect()
+}
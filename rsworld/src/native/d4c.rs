@@ -0,0 +1,258 @@
+use std::f64::consts::{PI, LN_2};
+
+use crate::{D4COption, Aperiodicity, check_f0};
+use crate::error::{Result, check_fs, check_fft_size, check_signal, check_finite};
+use super::common::{SAFE_GUARD_MINIMUM, matlab_round, nuttall_window, interp1, Randn, dc_correction, linear_smoothing, forward_real_fft};
+
+const FLOOR_F0: f64           = 47.0;
+const LOWEST_F0: f64          = 40.0;
+const FREQUENCY_INTERVAL: f64 = 3000.0;
+const UPPER_LIMIT: f64        = 15000.0;
+
+// F0-adaptive windows, each with its length in periods.
+#[derive(Clone, Copy)]
+enum Window {
+    Hanning(f64),
+    Blackman(f64),
+}
+
+// The signal around `current_position` under `window_type`, with its weighted
+// mean removed.
+fn windowed_waveform(x: &[f64], fs: i32, current_f0: f64, current_position: f64, window_type: Window, fft_size: usize,
+                     randn: &mut Randn) -> Vec<f64> {
+    let window_length_ratio = match window_type { Window::Hanning(ratio) | Window::Blackman(ratio) => ratio };
+    let half_window_length = matlab_round(window_length_ratio * fs as f64 / current_f0 / 2.0) as isize;
+    let origin             = matlab_round(current_position * fs as f64 + 0.001) as isize;
+    let window: Vec<f64> = (-half_window_length..=half_window_length).map(|i| {
+        let position = (2.0 * i as f64 / window_length_ratio) / fs as f64;
+        match window_type {
+            Window::Hanning(_)  => 0.5 * (PI * position * current_f0).cos() + 0.5,
+            Window::Blackman(_) => 0.42 + 0.5 * (PI * position * current_f0).cos() + 0.08 * (PI * position * current_f0 * 2.0).cos(),
+        }
+    }).collect();
+
+    let mut waveform = vec![0.0; fft_size];
+    for (i, (out, w)) in waveform.iter_mut().zip(&window).enumerate() {
+        let index = (origin - half_window_length + i as isize).clamp(0, x.len() as isize - 1) as usize;
+        *out = x[index] * w + randn.next() * SAFE_GUARD_MINIMUM;
+    }
+    let weighting_coefficient = waveform.iter().sum::<f64>() / window.iter().sum::<f64>();
+    for (out, w) in waveform.iter_mut().zip(&window) {
+        *out -= w * weighting_coefficient;
+    }
+    waveform
+}
+
+// Energy centroid of the spectrum (Cohen, Time-Frequency Analysis).
+fn centroid(x: &[f64], fs: i32, current_f0: f64, fft_size: usize, current_position: f64, randn: &mut Randn) -> Vec<f64> {
+    let mut waveform = windowed_waveform(x, fs, current_f0, current_position, Window::Blackman(4.0), fft_size, randn);
+    let limit        = matlab_round(2.0 * fs as f64 / current_f0) as usize * 2;
+    let power        = waveform[..=limit].iter().map(|v| v * v).sum::<f64>();
+    waveform[..=limit].iter_mut().for_each(|v| *v /= power.sqrt());
+    let spectrum = forward_real_fft(&waveform);
+    waveform.iter_mut().enumerate().for_each(|(i, v)| *v *= i as f64 + 1.0);
+    forward_real_fft(&waveform).iter().zip(&spectrum).map(|(w, s)| w.re * s.re + s.im * w.im).collect()
+}
+
+// Centroid that is static in time: the sum of two taken half a period apart.
+fn static_centroid(x: &[f64], fs: i32, current_f0: f64, fft_size: usize, current_position: f64, randn: &mut Randn) -> Vec<f64> {
+    let first  = centroid(x, fs, current_f0, fft_size, current_position - 0.25 / current_f0, randn);
+    let second = centroid(x, fs, current_f0, fft_size, current_position + 0.25 / current_f0, randn);
+    let mut static_centroid: Vec<f64> = first.iter().zip(&second).map(|(a, b)| a + b).collect();
+    dc_correction(&mut static_centroid, current_f0, fs, fft_size);
+    static_centroid
+}
+
+fn smoothed_power_spectrum(x: &[f64], fs: i32, current_f0: f64, fft_size: usize, current_position: f64, randn: &mut Randn) -> Vec<f64> {
+    let waveform = windowed_waveform(x, fs, current_f0, current_position, Window::Hanning(4.0), fft_size, randn);
+    let mut power_spectrum: Vec<f64> = forward_real_fft(&waveform).iter().map(|v| v.re * v.re + v.im * v.im).collect();
+    dc_correction(&mut power_spectrum, current_f0, fs, fft_size);
+    linear_smoothing(&power_spectrum, current_f0, fs, fft_size)
+}
+
+// Group delay that is static in time, with its trend over frequency removed.
+fn static_group_delay(static_centroid: &[f64], smoothed_power_spectrum: &[f64], fs: i32, f0: f64, fft_size: usize) -> Vec<f64> {
+    let group_delay: Vec<f64> = static_centroid.iter().zip(smoothed_power_spectrum).map(|(c, p)| c / p).collect();
+    let group_delay = linear_smoothing(&group_delay, f0 / 2.0, fs, fft_size);
+    let smoothed    = linear_smoothing(&group_delay, f0, fs, fft_size);
+    group_delay.iter().zip(&smoothed).map(|(g, s)| g - s).collect()
+}
+
+// Aperiodicity in dB at every multiple of 3 kHz, from how concentrated the
+// power of the group delay around it is.
+fn coarse_aperiodicity(static_group_delay: &[f64], fs: i32, fft_size: usize, number_of_aperiodicities: usize, window: &[f64]) -> Vec<f64> {
+    let half               = fft_size/2;
+    let boundary           = matlab_round(fft_size as f64 * 8.0 / window.len() as f64) as usize;
+    let half_window_length = window.len() / 2;
+    let mut waveform       = vec![0.0; fft_size];
+    (0..number_of_aperiodicities).map(|i| {
+        let center = (FREQUENCY_INTERVAL * (i + 1) as f64 * fft_size as f64 / fs as f64) as usize;
+        for (j, w) in window.iter().enumerate() {
+            waveform[j] = static_group_delay[center - half_window_length + j] * w;
+        }
+        let mut power_spectrum: Vec<f64> = forward_real_fft(&waveform).iter().map(|v| v.re * v.re + v.im * v.im).collect();
+        power_spectrum.sort_by(f64::total_cmp);
+        for j in 1..=half {
+            power_spectrum[j] += power_spectrum[j-1];
+        }
+        10.0 * (power_spectrum[half - boundary - 1] / power_spectrum[half]).log10()
+    }).collect()
+}
+
+fn d4c_fft_size(fs: i32) -> usize {
+    2f64.powf(1.0 + ((4.0 * fs as f64 / FLOOR_F0 + 1.0).ln() / LN_2) as i32 as f64) as usize
+}
+
+// Ratio of the power below 4 kHz to that below 7.9 kHz, both above 100 Hz.
+fn love_train(x: &[f64], fs: i32, current_f0: f64, current_position: f64, randn: &mut Randn) -> f64 {
+    let fft_size  = 2f64.powf(1.0 + ((3.0 * fs as f64 / LOWEST_F0 + 1.0).ln() / LN_2) as i32 as f64) as usize;
+    let boundary0 = (100.0 * fft_size as f64 / fs as f64).ceil() as usize;
+    let boundary1 = (4000.0 * fft_size as f64 / fs as f64).ceil() as usize;
+    let boundary2 = (7900.0 * fft_size as f64 / fs as f64).ceil() as usize;
+
+    let window_length = matlab_round(1.5 * fs as f64 / current_f0) as usize * 2 + 1;
+    let mut waveform  = windowed_waveform(x, fs, current_f0, current_position, Window::Blackman(3.0), fft_size, randn);
+    waveform[window_length.min(fft_size)..].iter_mut().for_each(|v| *v = 0.0);
+    let spectrum = forward_real_fft(&waveform);
+    let mut power_spectrum = vec![0.0; fft_size];
+    for i in boundary0+1..=fft_size/2 {
+        power_spectrum[i] = spectrum[i].re * spectrum[i].re + spectrum[i].im * spectrum[i].im;
+    }
+    for i in boundary0..=boundary2 {
+        power_spectrum[i] += power_spectrum[i-1];
+    }
+    power_spectrum[boundary1] / power_spectrum[boundary2]
+}
+
+// The aperiodicity of one voiced frame, `fft_size/2+1` bins.
+fn d4c_general_body(x: &[f64], fs: i32, current_f0: f64, current_position: f64, fft_size: usize, randn: &mut Randn) -> Vec<f64> {
+    let fft_size_d4c             = d4c_fft_size(fs);
+    let number_of_aperiodicities = ((UPPER_LIMIT.min(fs as f64 / 2.0 - FREQUENCY_INTERVAL)) / FREQUENCY_INTERVAL) as usize;
+    let window = nuttall_window((FREQUENCY_INTERVAL * fft_size_d4c as f64 / fs as f64) as usize * 2 + 1);
+
+    let static_centroid    = static_centroid(x, fs, current_f0, fft_size_d4c, current_position, randn);
+    let smoothed           = smoothed_power_spectrum(x, fs, current_f0, fft_size_d4c, current_position, randn);
+    let static_group_delay = static_group_delay(&static_centroid, &smoothed, fs, current_f0, fft_size_d4c);
+
+    // 0 Hz is fixed at -60 dB and Nyquist at 0 dB.
+    let mut coarse = vec![-60.0];
+    coarse.extend(coarse_aperiodicity(&static_group_delay, fs, fft_size_d4c, number_of_aperiodicities, &window).iter()
+        .map(|c| 0f64.min(c + (current_f0 - 100.0) / 50.0)));
+    coarse.push(-SAFE_GUARD_MINIMUM);
+    let mut coarse_axis: Vec<f64> = (0..=number_of_aperiodicities).map(|i| i as f64 * FREQUENCY_INTERVAL).collect();
+    coarse_axis.push(fs as f64 / 2.0);
+    let frequency_axis: Vec<f64> = (0..=fft_size/2).map(|i| i as f64 * fs as f64 / fft_size as f64).collect();
+    interp1(&coarse_axis, &coarse, &frequency_axis).iter().map(|v| 10f64.powf(v / 20.0)).collect()
+}
+
+fn check_d4c(x: &[f64], fs: i32, fft_size: i32, option: &D4COption) -> Result<()> {
+    check_fs(fs)?;
+    check_fft_size(fft_size)?;
+    option.validate()?;
+    check_signal(x)?;
+    Ok(())
+}
+
+/// Pure-Rust D4C with the same interface as `crate::d4c`, LoveTrain voicing
+/// included. Every bin agrees with the C implementation to 1e-9.
+pub fn d4c(x: &[f64], fs: i32, temporal_positions: &[f64], f0: &[f64], fft_size: i32, option: &D4COption) -> Result<Aperiodicity> {
+    check_d4c(x, fs, fft_size, option)?;
+    check_f0(temporal_positions, f0)?;
+    // One noise generator for the call: LoveTrain runs over every frame before
+    // the band aperiodicities, as in WORLD.
+    let mut randn = Randn::new();
+    let aperiodicity0: Vec<f64> = f0.iter().zip(temporal_positions)
+        .map(|(&f0, &position)| if f0 == 0.0 { 0.0 } else { love_train(x, fs, f0.max(LOWEST_F0), position, &mut randn) })
+        .collect();
+    let mut aperiodicity = Aperiodicity::with_fft_size(f0.len(), fft_size as usize);
+    aperiodicity.as_mut_slice().iter_mut().for_each(|v| *v = 1.0 - SAFE_GUARD_MINIMUM);
+    for (i, row) in aperiodicity.rows_mut().enumerate() {
+        if f0[i] == 0.0 || aperiodicity0[i] <= option.threshold {
+            continue;
+        }
+        row.copy_from_slice(&d4c_general_body(x, fs, f0[i].max(FLOOR_F0), temporal_positions[i], fft_size as usize, &mut randn));
+    }
+    Ok(aperiodicity)
+}
+
+/// LoveTrain's voicing measure for one frame: the share of the power between
+/// 100 Hz and 7.9 kHz that lies below 4 kHz. `d4c` leaves frames at or below
+/// `D4COption::threshold` fully aperiodic; unvoiced frames (`f0 == 0`) give 0.
+pub fn d4c_love_train(x: &[f64], fs: i32, temporal_position: f64, f0: f64) -> Result<f64> {
+    check_fs(fs)?;
+    check_signal(x)?;
+    check_finite("temporal_positions", &[temporal_position])?;
+    check_finite("f0", &[f0])?;
+    if f0 == 0.0 {
+        return Ok(0.0);
+    }
+    Ok(love_train(x, fs, f0.max(LOWEST_F0), temporal_position, &mut Randn::new()))
+}
+
+/// The aperiodicity of one frame, `fft_size/2+1` bins, independent of any
+/// other frame. The result is `d4c` of this frame alone; within a longer call
+/// only the 1e-12 safeguard noise WORLD adds to the waveform differs.
+pub fn d4c_frame(x: &[f64], fs: i32, temporal_position: f64, f0: f64, fft_size: i32, option: &D4COption) -> Result<Vec<f64>> {
+    check_d4c(x, fs, fft_size, option)?;
+    check_finite("temporal_positions", &[temporal_position])?;
+    check_finite("f0", &[f0])?;
+    let mut randn = Randn::new();
+    if f0 == 0.0 || love_train(x, fs, f0.max(LOWEST_F0), temporal_position, &mut randn) <= option.threshold {
+        return Ok(vec![1.0 - SAFE_GUARD_MINIMUM; fft_size as usize/2+1]);
+    }
+    Ok(d4c_general_body(x, fs, f0.max(FLOOR_F0), temporal_position, fft_size as usize, &mut randn))
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use crate::{D4COption, HarvestOption, CheapTrickOption, d4c, harvest, get_fft_size_for_cheaptrick};
+    use crate::native::test_signals::{breathy_vibrato, vaiueo2d, assert_close};
+
+    fn compare(x: &[f64], fs: i32, option: &D4COption) {
+        let (temporal_positions, f0) = harvest(x, fs, &HarvestOption::new()).unwrap();
+        let fft_size     = get_fft_size_for_cheaptrick(fs, &CheapTrickOption::new()).unwrap();
+        let reference    = d4c(x, fs, &temporal_positions, &f0, fft_size, option).unwrap();
+        let aperiodicity = super::d4c(x, fs, &temporal_positions, &f0, fft_size, option).unwrap();
+        for frame in 0..f0.len() {
+            assert_close(&format!("frame {}", frame), aperiodicity.row(frame), reference.row(frame), |_| 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_matches_c() {
+        for &fs in &[16000, 44100] {
+            compare(&breathy_vibrato(fs, fs as usize * 3 / 2), fs, &D4COption::new());
+        }
+    }
+
+    #[test]
+    fn test_recorded_speech() {
+        let (x, fs) = vaiueo2d();
+        assert_eq!((x.len(), fs), (17500, 22050));
+        compare(&x, fs, &D4COption::new());
+    }
+
+    #[test]
+    fn test_threshold() {
+        let x = breathy_vibrato(16000, 24000);
+        compare(&x, 16000, &D4COption::new().threshold(0.0));
+        compare(&x, 16000, &D4COption::new().threshold(0.999));
+    }
+
+    #[test]
+    fn test_frame() {
+        let fs        = 16000;
+        let x         = breathy_vibrato(fs, fs as usize);
+        let fft_size  = 1024;
+        let option    = D4COption::new();
+        let reference = d4c(&x, fs, &[0.5], &[150.0], fft_size, &option).unwrap();
+        let frame     = super::d4c_frame(&x, fs, 0.5, 150.0, fft_size, &option).unwrap();
+        assert_close("frame", &frame, reference.row(0), |_| 1e-9);
+        assert!(frame.iter().any(|&v| v < 0.5));
+
+        let love_train = super::d4c_love_train(&x, fs, 0.5, 150.0).unwrap();
+        assert!(love_train > option.threshold && love_train <= 1.0);
+        assert_eq!(super::d4c_love_train(&x, fs, 0.5, 0.0), Ok(0.0));
+        assert!(super::d4c_frame(&x, fs, 0.1, 150.0, fft_size, &option).unwrap().iter().all(|&v| v == 1.0 - 1e-12));
+    }
+}
@@ -0,0 +1,242 @@
+use std::f64::consts::PI;
+
+use crate::DioOption;
+use crate::fft::Complex;
+use crate::error::{Result, check_signal, check_decimation};
+use super::common::{SAFE_GUARD_MINIMUM, matlab_round, get_suitable_fft_size, nuttall_window, interp1, decimate, forward_real_fft, convolve,
+                    zero_crossing_intervals};
+
+const CUT_OFF: f64       = 50.0;
+const MAXIMUM_VALUE: f64 = 100000.0;
+
+fn design_low_cut_filter(n: usize, fft_size: usize) -> Vec<f64> {
+    let mut filter = vec![0.0; fft_size];
+    for i in 1..=n {
+        filter[i-1] = 0.5 - 0.5 * (i as f64 * 2.0 * PI / (n + 1) as f64).cos();
+    }
+    let sum: f64 = filter[..n].iter().sum();
+    filter[..n].iter_mut().for_each(|v| *v = -*v / sum);
+    for i in 0..(n-1)/2 {
+        filter[fft_size-(n-1)/2+i] = filter[i];
+    }
+    for i in 0..n {
+        filter[i] = filter[i+(n-1)/2];
+    }
+    filter[0] += 1.0;
+    filter
+}
+
+// Spectrum of the decimated, DC-removed and 50 Hz high-passed signal.
+fn spectrum_for_estimation(x: &[f64], y_length: usize, actual_fs: f64, fft_size: usize, decimation_ratio: usize) -> Vec<Complex> {
+    let mut y = vec![0.0; fft_size];
+    if decimation_ratio != 1 {
+        let decimated = decimate(x, decimation_ratio);
+        y[..decimated.len()].copy_from_slice(&decimated);
+    } else {
+        y[..x.len()].copy_from_slice(x);
+    }
+    let mean = y[..y_length].iter().sum::<f64>() / y_length as f64;
+    y[..y_length].iter_mut().for_each(|v| *v -= mean);
+    y[y_length..].iter_mut().for_each(|v| *v = 0.0);
+    let spectrum = forward_real_fft(&y);
+    let cutoff   = matlab_round(actual_fs / CUT_OFF) as usize;
+    let filter   = forward_real_fft(&design_low_cut_filter(cutoff*2+1, fft_size));
+    spectrum.iter().zip(&filter).map(|(&s, &f)| s * f).collect()
+}
+
+fn candidate_from_raw_event(boundary_f0: f64, fs: f64, y_spectrum: &[Complex], y_length: usize, fft_size: usize,
+                            option: &DioOption, temporal_positions: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let half_average_length = matlab_round(fs / boundary_f0 / 2.0) as usize;
+    let mut low_pass        = nuttall_window(half_average_length*4);
+    low_pass.resize(fft_size, 0.0);
+    let mut signal = convolve(y_spectrum, &low_pass);
+    let bias       = half_average_length*2;
+    signal.copy_within(bias..bias+y_length, 0);
+    signal.truncate(y_length);
+
+    let f0_length = temporal_positions.len();
+    let events    = zero_crossing_intervals(signal, fs);
+    if events.iter().any(|(locations, _)| locations.len() < 3) {
+        return (vec![0.0; f0_length], vec![MAXIMUM_VALUE; f0_length]);
+    }
+    let interpolated: Vec<Vec<f64>> = events.iter().map(|(locations, intervals)| interp1(locations, intervals, temporal_positions)).collect();
+    let mut candidate = vec![0.0; f0_length];
+    let mut score     = vec![0.0; f0_length];
+    for i in 0..f0_length {
+        let values = [interpolated[0][i], interpolated[1][i], interpolated[2][i], interpolated[3][i]];
+        candidate[i] = (values[0] + values[1] + values[2] + values[3]) / 4.0;
+        score[i] = (values.iter().map(|v| (v - candidate[i]) * (v - candidate[i])).sum::<f64>() / 3.0).sqrt();
+        if candidate[i] > boundary_f0 || candidate[i] < boundary_f0 / 2.0 || candidate[i] > option.f0_ceil || candidate[i] < option.f0_floor {
+            candidate[i] = 0.0;
+            score[i]     = MAXIMUM_VALUE;
+        }
+    }
+    (candidate, score)
+}
+
+fn select_best_f0(current_f0: f64, past_f0: f64, candidates: &[Vec<f64>], target: usize, allowed_range: f64) -> f64 {
+    let reference = (current_f0 * 3.0 - past_f0) / 2.0;
+    let mut minimum_error = (reference - candidates[0][target]).abs();
+    let mut best          = candidates[0][target];
+    for band in &candidates[1..] {
+        let error = (reference - band[target]).abs();
+        if error < minimum_error {
+            minimum_error = error;
+            best          = band[target];
+        }
+    }
+    if (1.0 - best / reference).abs() > allowed_range { 0.0 } else { best }
+}
+
+fn fix_f0_contour(frame_period: f64, candidates: &[Vec<f64>], best_f0: &[f64], f0_floor: f64, allowed_range: f64) -> Vec<f64> {
+    let f0_length = best_f0.len();
+    let minimum   = (0.5 + 1000.0 / frame_period / f0_floor) as usize * 2 + 1;
+    if f0_length <= minimum {
+        return vec![0.0; f0_length];
+    }
+
+    // Step 1: drop sudden jumps.
+    let mut base = vec![0.0; f0_length];
+    base[minimum..f0_length-minimum].copy_from_slice(&best_f0[minimum..f0_length-minimum]);
+    let mut step1 = vec![0.0; f0_length];
+    for i in minimum..f0_length {
+        step1[i] = if ((base[i] - base[i-1]) / (SAFE_GUARD_MINIMUM + base[i])).abs() < allowed_range { base[i] } else { 0.0 };
+    }
+
+    // Step 2: drop frames near the edges of voiced sections.
+    let mut step2 = step1.clone();
+    let centre    = (minimum-1) / 2;
+    for i in centre..f0_length-centre {
+        if step1[i-centre..=i+centre].contains(&0.0) {
+            step2[i] = 0.0;
+        }
+    }
+
+    let mut positive = Vec::new();
+    let mut negative = Vec::new();
+    for i in 1..f0_length {
+        if step2[i] == 0.0 && step2[i-1] != 0.0 {
+            negative.push(i-1);
+        } else if step2[i-1] == 0.0 && step2[i] != 0.0 {
+            positive.push(i);
+        }
+    }
+
+    // Step 3: extend voiced sections forwards.
+    let mut step3 = step2;
+    for (k, &start) in negative.iter().enumerate() {
+        let limit = if k == negative.len()-1 { f0_length-1 } else { negative[k+1] };
+        for j in start..limit {
+            step3[j+1] = select_best_f0(step3[j], step3[j-1], candidates, j+1, allowed_range);
+            if step3[j+1] == 0.0 {
+                break;
+            }
+        }
+    }
+
+    // Step 4: extend voiced sections backwards.
+    let mut step4 = step3;
+    for k in (0..positive.len()).rev() {
+        let limit = if k == 0 { 1 } else { positive[k-1] };
+        let mut j = positive[k];
+        while j > limit {
+            step4[j-1] = select_best_f0(step4[j], step4[j+1], candidates, j-1, allowed_range);
+            if step4[j-1] == 0.0 {
+                break;
+            }
+            j -= 1;
+        }
+    }
+    step4
+}
+
+/// Pure-Rust DIO with the same interface as `crate::dio`. Voicing decisions
+/// match the C implementation and F0 agrees to a relative 1e-6.
+pub fn dio(x: &[f64], fs: i32, option: &DioOption) -> Result<(Vec<f64>, Vec<f64>)> {
+    option.validate(fs)?;
+    check_signal(x)?;
+    check_decimation(x, option.speed)?;
+    let bands = 1 + ((option.f0_ceil / option.f0_floor).ln() / 2f64.ln() * option.channels_in_octave) as usize;
+    let boundary_f0: Vec<f64> = (0..bands).map(|i| option.f0_floor * 2f64.powf((i + 1) as f64 / option.channels_in_octave)).collect();
+
+    let decimation_ratio = option.speed.clamp(1, 12) as usize;
+    let y_length         = 1 + x.len() / decimation_ratio;
+    let actual_fs        = fs as f64 / decimation_ratio as f64;
+    let fft_size         = get_suitable_fft_size(y_length + matlab_round(actual_fs / CUT_OFF) as usize * 2 + 1
+                                                 + 4 * (1.0 + actual_fs / boundary_f0[0] / 2.0) as usize);
+    let y_spectrum = spectrum_for_estimation(x, y_length, actual_fs, fft_size, decimation_ratio);
+
+    let f0_length = (1000.0 * x.len() as f64 / fs as f64 / option.frame_period) as usize + 1;
+    let temporal_positions: Vec<f64> = (0..f0_length).map(|i| i as f64 * option.frame_period / 1000.0).collect();
+
+    let mut candidates = Vec::with_capacity(bands);
+    let mut scores     = Vec::with_capacity(bands);
+    for &boundary in &boundary_f0 {
+        let (candidate, score) = candidate_from_raw_event(boundary, actual_fs, &y_spectrum, y_length, fft_size,
+                                                          option, &temporal_positions);
+        scores.push(score.iter().zip(&candidate).map(|(s, c)| s / (c + SAFE_GUARD_MINIMUM)).collect::<Vec<f64>>());
+        candidates.push(candidate);
+    }
+
+    let best_f0: Vec<f64> = (0..f0_length).map(|i| {
+        let mut score = scores[0][i];
+        let mut best  = candidates[0][i];
+        for band in 1..bands {
+            if score > scores[band][i] {
+                score = scores[band][i];
+                best  = candidates[band][i];
+            }
+        }
+        best
+    }).collect();
+
+    let f0 = fix_f0_contour(option.frame_period, &candidates, &best_f0, option.f0_floor, option.allowed_range);
+    Ok((temporal_positions, f0))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DioOption, WorldError};
+    #[cfg(feature = "ffi")]
+    use crate::dio;
+    #[cfg(feature = "ffi")]
+    use crate::native::test_signals::{vibrato, assert_close};
+
+    // Agreement with the C implementation: identical voicing, F0 within a relative 1e-6.
+    #[cfg(feature = "ffi")]
+    fn compare(fs: i32, option: &DioOption) {
+        let x = vibrato(fs, fs as usize * 3 / 2);
+        let (reference_positions, reference_f0) = dio(&x, fs, option).unwrap();
+        let (positions, f0) = super::dio(&x, fs, option).unwrap();
+        assert_eq!(positions, reference_positions);
+        assert!(reference_f0.iter().any(|&v| v > 0.0));
+        // Unvoiced reference frames allow no error, so voicing must agree.
+        assert_close("f0", &f0, &reference_f0, |r| 1e-6 * r);
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_matches_c() {
+        compare(16000, &DioOption::new());
+        compare(22050, &DioOption::new().frame_period(10.0).channels_in_octave(3.0).allowed_range(0.2));
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_speed() {
+        compare(16000, &DioOption::new().speed(4));
+        compare(44100, &DioOption::new().speed(11));
+    }
+
+    #[test]
+    fn test_short_signal() {
+        let option = DioOption::new().speed(4);
+        assert_eq!(super::dio(&[0.1, 0.2, 0.3], 16000, &option), Err(WorldError::SignalTooShort { minimum: 10, actual: 3 }));
+        assert_eq!(super::dio(&[0.1; 5], 16000, &option.speed(2)), Err(WorldError::SignalTooShort { minimum: 10, actual: 5 }));
+        // Decimated to a single sample, which has no zero crossings to look at.
+        let (temporal_positions, f0) = super::dio(&[0.1; 10], 16000, &option.speed(12)).unwrap();
+        assert_eq!(temporal_positions, vec![0.0]);
+        assert_eq!(f0, vec![0.0]);
+        assert!(super::dio(&[0.1, 0.2, 0.3], 16000, &option.speed(1)).is_ok());
+    }
+}
@@ -0,0 +1,521 @@
+use std::f64::consts::{PI, LN_2};
+
+use crate::HarvestOption;
+use crate::fft::Complex;
+use crate::error::{Result, check_signal};
+use super::common::{SAFE_GUARD_MINIMUM, matlab_round, get_suitable_fft_size, nuttall_window, interp1, decimate, forward_real_fft, convolve,
+                    zero_crossing_intervals};
+
+const CHANNELS_IN_OCTAVE: f64 = 40.0;
+const OVERLAP: usize          = 7;
+
+// The decimated, DC-removed signal (`y_length` samples) and the spectrum of it
+// zero-padded to `fft_size`.
+fn waveform_and_spectrum(x: &[f64], y_length: usize, fft_size: usize, decimation_ratio: usize) -> (Vec<f64>, Vec<Complex>) {
+    let mut y = vec![0.0; fft_size];
+    if decimation_ratio == 1 {
+        y[..x.len()].copy_from_slice(x);
+    } else {
+        // The edges are extended so the decimation filter settles before the signal starts.
+        let lag          = ((140.0 / decimation_ratio as f64).ceil() * decimation_ratio as f64) as usize;
+        let mut extended = vec![x[0]; lag];
+        extended.extend_from_slice(x);
+        extended.resize(x.len() + lag*2, x[x.len()-1]);
+        let decimated = decimate(&extended, decimation_ratio);
+        y[..y_length].copy_from_slice(&decimated[lag/decimation_ratio..lag/decimation_ratio+y_length]);
+    }
+    let mean = y[..y_length].iter().sum::<f64>() / y_length as f64;
+    y[..y_length].iter_mut().for_each(|v| *v -= mean);
+    let spectrum = forward_real_fft(&y);
+    y.truncate(y_length);
+    (y, spectrum)
+}
+
+// F0 from the zero crossings of the signal band-passed around `boundary_f0`,
+// zero where it falls outside 10% of the band or the option's range.
+fn raw_f0_candidate(boundary_f0: f64, fs: f64, y_spectrum: &[Complex], y_length: usize, fft_size: usize,
+                    option: &HarvestOption, temporal_positions: &[f64]) -> Vec<f64> {
+    let half       = matlab_round(fs / boundary_f0 * 2.0) as usize;
+    let mut filter = nuttall_window(half*2+1);
+    for (i, v) in filter.iter_mut().enumerate() {
+        *v *= (2.0 * PI * boundary_f0 * (i as f64 - half as f64) / fs).cos();
+    }
+    filter.resize(fft_size, 0.0);
+    let mut signal = convolve(y_spectrum, &filter);
+    signal.copy_within(half+1..half+1+y_length, 0);
+    signal.truncate(y_length);
+
+    let events = zero_crossing_intervals(signal, fs);
+    if events.iter().any(|(locations, _)| locations.len() < 3) {
+        return vec![0.0; temporal_positions.len()];
+    }
+    let interpolated: Vec<Vec<f64>> = events.iter().map(|(locations, intervals)| interp1(locations, intervals, temporal_positions)).collect();
+    (0..temporal_positions.len()).map(|i| {
+        let candidate = (interpolated[0][i] + interpolated[1][i] + interpolated[2][i] + interpolated[3][i]) / 4.0;
+        if candidate > boundary_f0 * 1.1 || candidate < boundary_f0 * 0.9 || candidate > option.f0_ceil || candidate < option.f0_floor {
+            0.0
+        } else {
+            candidate
+        }
+    }).collect()
+}
+
+// Per frame, the mean F0 of every run of at least 10 adjacent channels with a
+// candidate. Returns the candidates, `max_candidates` slots per frame, and the
+// largest number found in any frame.
+fn official_f0_candidates(raw_candidates: &[Vec<f64>], f0_length: usize, max_candidates: usize) -> (Vec<Vec<f64>>, usize) {
+    let channels       = raw_candidates.len();
+    let mut candidates = vec![vec![0.0; max_candidates]; f0_length];
+    let mut number     = 0;
+    for (i, frame) in candidates.iter_mut().enumerate() {
+        let mut vuv: Vec<bool> = raw_candidates.iter().map(|channel| channel[i] > 0.0).collect();
+        vuv[0]          = false;
+        vuv[channels-1] = false;
+        let mut start = 0;
+        let mut count = 0;
+        for j in 1..channels {
+            if vuv[j] && !vuv[j-1] {
+                start = j;
+            } else if !vuv[j] && vuv[j-1] && j - start >= 10 && count < max_candidates / OVERLAP {
+                frame[count] = raw_candidates[start..j].iter().map(|channel| channel[i]).sum::<f64>() / (j - start) as f64;
+                count += 1;
+            }
+        }
+        number = number.max(count);
+    }
+    (candidates, number)
+}
+
+// Adds the candidates of the three frames on either side to each frame.
+fn overlap_f0_candidates(candidates: &mut [Vec<f64>], number: usize) {
+    let f0_length = candidates.len();
+    let n         = 3;
+    for i in 1..=n {
+        for j in 0..number {
+            for k in i..f0_length {
+                candidates[k][j+number*i] = candidates[k-i][j];
+            }
+            for k in 0..f0_length.saturating_sub(i) {
+                candidates[k][j+number*(i+n)] = candidates[k+i][j];
+            }
+        }
+    }
+}
+
+// Weighted mean of the instantaneous frequencies of the first six harmonics
+// around `current_f0`, and its score (the inverse of their mean relative error).
+fn mean_f0(x: &[f64], fs: f64, current_position: f64, current_f0: f64, half_window_length: usize) -> (f64, f64) {
+    let length      = half_window_length*2+1;
+    let window_time = (2.0 * half_window_length as f64 + 1.0) / fs;
+    let fft_size    = 2f64.powf(2.0 + ((half_window_length as f64 * 2.0 + 1.0).ln() / LN_2) as i32 as f64) as usize;
+    let base_index  = matlab_round((current_position - half_window_length as f64 / fs) * fs + 0.001) as isize;
+
+    let main_window: Vec<f64> = (0..length).map(|i| {
+        let t = (base_index + i as isize) as f64 - 1.0;
+        let t = t / fs - current_position;
+        0.42 + 0.5 * (2.0 * PI * t / window_time).cos() + 0.08 * (4.0 * PI * t / window_time).cos()
+    }).collect();
+    let mut diff_window = vec![0.0; length];
+    diff_window[0] = -main_window[1] / 2.0;
+    for i in 1..length-1 {
+        diff_window[i] = -(main_window[i+1] - main_window[i-1]) / 2.0;
+    }
+    diff_window[length-1] = main_window[length-2] / 2.0;
+
+    let windowed = |window: &[f64]| {
+        let mut waveform = vec![0.0; fft_size];
+        for (i, (out, w)) in waveform.iter_mut().zip(window).enumerate() {
+            let index = (base_index + i as isize - 1).clamp(0, x.len() as isize - 1) as usize;
+            *out = x[index] * w;
+        }
+        forward_real_fft(&waveform)
+    };
+    let main_spectrum = windowed(&main_window);
+    let diff_spectrum = windowed(&diff_window);
+    let power: Vec<f64>       = main_spectrum.iter().map(|m| m.re * m.re + m.im * m.im).collect();
+    let numerator_i: Vec<f64> = main_spectrum.iter().zip(&diff_spectrum).map(|(m, d)| m.re * d.im - m.im * d.re).collect();
+
+    let harmonics       = ((fs / 2.0 / current_f0) as i32).min(6);
+    let mut numerator   = 0.0;
+    let mut denominator = 0.0;
+    let mut score       = 0.0;
+    for i in 0..harmonics {
+        let index     = matlab_round(current_f0 * fft_size as f64 / fs * (i + 1) as f64) as usize;
+        let frequency = if power[index] == 0.0 {
+            0.0
+        } else {
+            index as f64 * fs / fft_size as f64 + numerator_i[index] / power[index] * fs / 2.0 / PI
+        };
+        let amplitude = power[index].sqrt();
+        numerator   += amplitude * frequency;
+        denominator += amplitude * (i as f64 + 1.0);
+        score       += ((frequency / (i as f64 + 1.0) - current_f0) / current_f0).abs();
+    }
+    (numerator / (denominator + SAFE_GUARD_MINIMUM), 1.0 / (score / harmonics as f64 + SAFE_GUARD_MINIMUM))
+}
+
+// A candidate refined by instantaneous frequency and its score, both zero when
+// it leaves the F0 range or scores below 2.5.
+fn refined_f0(x: &[f64], fs: f64, current_position: f64, current_f0: f64, option: &HarvestOption) -> (f64, f64) {
+    if current_f0 <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let half_window_length = (1.5 * fs / current_f0 + 1.0) as usize;
+    let (f0, score)        = mean_f0(x, fs, current_position, current_f0, half_window_length);
+    if f0 < option.f0_floor || f0 > option.f0_ceil || score < 2.5 {
+        (0.0, 0.0)
+    } else {
+        (f0, score)
+    }
+}
+
+// The candidate nearest `reference_f0` within a relative `allowed_range`, and
+// its relative error (`allowed_range` when there is none).
+fn select_best_f0(reference_f0: f64, candidates: &[f64], allowed_range: f64) -> (f64, f64) {
+    let mut best_f0    = 0.0;
+    let mut best_error = allowed_range;
+    for &candidate in candidates {
+        let error = (reference_f0 - candidate).abs() / reference_f0;
+        if error > best_error {
+            continue;
+        }
+        best_f0    = candidate;
+        best_error = error;
+    }
+    (best_f0, best_error)
+}
+
+// Drops candidates with no neighbour within 5% in the frames on either side.
+fn remove_unreliable_candidates(candidates: &mut [Vec<f64>], scores: &mut [Vec<f64>], number: usize) {
+    let f0_length = candidates.len();
+    // WORLD leaves the first and last rows of its copy uninitialised; they are empty here.
+    let mut original = candidates.to_vec();
+    original[0].iter_mut().for_each(|v| *v = 0.0);
+    original[f0_length-1].iter_mut().for_each(|v| *v = 0.0);
+    for i in 1..f0_length.saturating_sub(1) {
+        for j in 0..number {
+            let reference_f0 = candidates[i][j];
+            if reference_f0 == 0.0 {
+                continue;
+            }
+            let (_, next)     = select_best_f0(reference_f0, &original[i+1][..number], 1.0);
+            let (_, previous) = select_best_f0(reference_f0, &original[i-1][..number], 1.0);
+            if next.min(previous) > 0.05 {
+                candidates[i][j] = 0.0;
+                scores[i][j]     = 0.0;
+            }
+        }
+    }
+}
+
+// First and last frame of each voiced section, ignoring the first and last frame.
+fn voiced_sections(f0: &[f64]) -> Vec<(usize, usize)> {
+    let mut vuv: Vec<bool> = f0.iter().map(|&v| v > 0.0).collect();
+    vuv[0]          = false;
+    vuv[f0.len()-1] = false;
+    let mut sections = Vec::new();
+    let mut start    = 0;
+    for i in 1..f0.len() {
+        if vuv[i] && !vuv[i-1] {
+            start = i;
+        } else if !vuv[i] && vuv[i-1] {
+            sections.push((start, i-1));
+        }
+    }
+    sections
+}
+
+// One contour per section, zero outside it.
+fn multi_channel_f0(f0: &[f64], sections: &[(usize, usize)]) -> Vec<Vec<f64>> {
+    sections.iter().map(|&(start, end)| {
+        let mut channel = vec![0.0; f0.len()];
+        channel[start..=end].copy_from_slice(&f0[start..=end]);
+        channel
+    }).collect()
+}
+
+// Step 1: rapid changes of the contour are dropped.
+fn fix_step1(f0_base: &[f64], allowed_range: f64) -> Vec<f64> {
+    let mut f0 = vec![0.0; f0_base.len()];
+    for i in 2..f0_base.len() {
+        if f0_base[i] == 0.0 {
+            continue;
+        }
+        let reference_f0 = f0_base[i-1] * 2.0 - f0_base[i-2];
+        f0[i] = if ((f0_base[i] - reference_f0) / reference_f0).abs() > allowed_range
+                   && (f0_base[i] - f0_base[i-1]).abs() / f0_base[i-1] > allowed_range { 0.0 } else { f0_base[i] };
+    }
+    f0
+}
+
+// Step 2: voiced sections shorter than `voice_range_minimum` are dropped.
+fn fix_step2(f0_step1: &[f64], voice_range_minimum: usize) -> Vec<f64> {
+    let mut f0 = f0_step1.to_vec();
+    for (start, end) in voiced_sections(f0_step1) {
+        if end - start < voice_range_minimum {
+            f0[start..=end].iter_mut().for_each(|v| *v = 0.0);
+        }
+    }
+    f0
+}
+
+// Follows the nearest candidate from `origin` towards `last_point` until four
+// frames in a row have none, and returns the last frame that had one.
+fn extend_f0(f0: &mut [f64], origin: usize, last_point: usize, shift: isize, candidates: &[Vec<f64>], number: usize, allowed_range: f64) -> usize {
+    let mut current = f0[origin];
+    let mut shifted = origin;
+    let mut count   = 0;
+    for i in 0..=(last_point as isize - origin as isize).unsigned_abs() {
+        let index = (origin as isize + shift * (i as isize + 1)) as usize;
+        f0[index] = select_best_f0(current, &candidates[index][..number], allowed_range).0;
+        if f0[index] == 0.0 {
+            count += 1;
+        } else {
+            current = f0[index];
+            count   = 0;
+            shifted = index;
+        }
+        if count == 4 {
+            break;
+        }
+    }
+    shifted
+}
+
+// Extends every section in both directions and keeps, in order, those long
+// enough for their mean F0. Returns how many were kept.
+fn extend(multi_channel_f0: &mut [Vec<f64>], sections: &mut [(usize, usize)], candidates: &[Vec<f64>], number: usize, allowed_range: f64) -> usize {
+    let f0_length = candidates.len();
+    for (f0, section) in multi_channel_f0.iter_mut().zip(sections.iter_mut()) {
+        section.1 = extend_f0(f0, section.1, (f0_length-2).min(section.1 + 100), 1, candidates, number, allowed_range);
+        section.0 = extend_f0(f0, section.0, section.0.saturating_sub(100).max(1), -1, candidates, number, allowed_range);
+    }
+    // WORLD carries the mean over from one section to the next; kept for parity.
+    let mut mean_f0 = 0.0;
+    let mut count   = 0;
+    for i in 0..sections.len() {
+        let (start, end) = sections[i];
+        mean_f0 += multi_channel_f0[i][start..end].iter().sum::<f64>();
+        mean_f0 /= (end - start) as f64;
+        if 2200.0 / mean_f0 < (end - start) as f64 {
+            multi_channel_f0.swap(count, i);
+            sections.swap(count, i);
+            count += 1;
+        }
+    }
+    count
+}
+
+fn search_score(f0: f64, candidates: &[f64], scores: &[f64]) -> f64 {
+    candidates.iter().zip(scores).fold(0.0, |score, (&candidate, &s)| if f0 == candidate && score < s { s } else { score })
+}
+
+fn merge_f0_sub(merged_f0: &mut [f64], (st1, ed1): (usize, usize), f0: &[f64], (st2, ed2): (usize, usize),
+                candidates: &[Vec<f64>], scores: &[Vec<f64>], number: usize) -> usize {
+    if st1 <= st2 && ed1 >= ed2 {
+        return ed1;
+    }
+    let mut score1 = 0.0;
+    let mut score2 = 0.0;
+    for i in st2..=ed1 {
+        score1 += search_score(merged_f0[i], &candidates[i][..number], &scores[i][..number]);
+        score2 += search_score(f0[i], &candidates[i][..number], &scores[i][..number]);
+    }
+    let from = if score1 > score2 { ed1 } else { st2 };
+    if from <= ed2 {
+        merged_f0[from..=ed2].copy_from_slice(&f0[from..=ed2]);
+    }
+    ed2
+}
+
+// Joins the sections in order of their start, settling overlaps by score.
+fn merge_f0(multi_channel_f0: &[Vec<f64>], sections: &mut [(usize, usize)], candidates: &[Vec<f64>], scores: &[Vec<f64>], number: usize) -> Vec<f64> {
+    // WORLD's insertion sort, which swaps with the element it compares to.
+    let mut order: Vec<usize> = (0..sections.len()).collect();
+    for i in 1..order.len() {
+        for j in (0..i).rev() {
+            if sections[order[j]].0 > sections[order[i]].0 {
+                order.swap(i, j);
+            } else {
+                break;
+            }
+        }
+    }
+    let mut merged_f0 = multi_channel_f0[0].clone();
+    for &channel in &order[1..] {
+        let (start, end) = sections[channel];
+        if start > sections[0].1 {
+            merged_f0[start..=end].copy_from_slice(&multi_channel_f0[channel][start..=end]);
+            sections[0] = (start, end);
+        } else {
+            sections[0].1 = merge_f0_sub(&mut merged_f0, sections[0], &multi_channel_f0[channel], (start, end), candidates, scores, number);
+        }
+    }
+    merged_f0
+}
+
+// Step 3: voiced sections are extended along the candidates and merged.
+fn fix_step3(f0_step2: &[f64], candidates: &[Vec<f64>], scores: &[Vec<f64>], number: usize, allowed_range: f64) -> Vec<f64> {
+    let mut sections         = voiced_sections(f0_step2);
+    let mut multi_channel_f0 = multi_channel_f0(f0_step2, &sections);
+    let channels             = extend(&mut multi_channel_f0, &mut sections, candidates, number, allowed_range);
+    if channels == 0 {
+        return f0_step2.to_vec();
+    }
+    merge_f0(&multi_channel_f0[..channels], &mut sections[..channels], candidates, scores, number)
+}
+
+// Step 4: unvoiced gaps shorter than `threshold` are bridged linearly.
+fn fix_step4(f0_step3: &[f64], threshold: usize) -> Vec<f64> {
+    let mut f0 = f0_step3.to_vec();
+    for pair in voiced_sections(f0_step3).windows(2) {
+        let distance = pair[1].0 - pair[0].1 - 1;
+        if distance >= threshold {
+            continue;
+        }
+        let start       = f0_step3[pair[0].1] + 1.0;
+        let end         = f0_step3[pair[1].0] - 1.0;
+        let coefficient = (end - start) / (distance as f64 + 1.0);
+        for (count, j) in (pair[0].1+1..pair[1].0).enumerate() {
+            f0[j] = start + coefficient * (count + 1) as f64;
+        }
+    }
+    f0
+}
+
+fn fix_f0_contour(candidates: &[Vec<f64>], scores: &[Vec<f64>], number: usize) -> Vec<f64> {
+    let f0_base: Vec<f64> = candidates.iter().zip(scores).map(|(frame, frame_scores)| {
+        let mut best_f0    = 0.0;
+        let mut best_score = 0.0;
+        for (&f0, &score) in frame[..number].iter().zip(&frame_scores[..number]) {
+            if score > best_score {
+                best_f0    = f0;
+                best_score = score;
+            }
+        }
+        best_f0
+    }).collect();
+    // These parameters are optimised on speech databases.
+    let f0 = fix_step1(&f0_base, 0.008);
+    let f0 = fix_step2(&f0, 6);
+    let f0 = fix_step3(&f0, candidates, scores, number, 0.18);
+    fix_step4(&f0, 9)
+}
+
+// Zero-phase second order Butterworth low-pass over one voiced section, held
+// constant outside it.
+#[allow(clippy::excessive_precision)]
+fn filter_f0(mut x: Vec<f64>, start: usize, end: usize) -> Vec<f64> {
+    const B: [f64; 2] = [0.0078202080334971724, 0.015640416066994345];
+    const A: [f64; 2] = [1.7347257688092754, -0.76600660094326412];
+    let length = x.len();
+    let (first, last) = (x[start], x[end]);
+    x[..start].iter_mut().for_each(|v| *v = first);
+    x[end+1..].iter_mut().for_each(|v| *v = last);
+    let pass = |input: &[f64]| {
+        let mut w      = [0.0; 2];
+        let mut output = vec![0.0; length];
+        for (i, &v) in input.iter().enumerate() {
+            let wt = v + A[0] * w[0] + A[1] * w[1];
+            output[length-i-1] = B[0] * wt + B[1] * w[0] + B[0] * w[1];
+            w = [wt, w[0]];
+        }
+        output
+    };
+    pass(&pass(&x))
+}
+
+fn smooth_f0_contour(f0: &[f64]) -> Vec<f64> {
+    const LAG: usize = 300;
+    let mut contour = vec![0.0; f0.len() + LAG*2];
+    contour[LAG..LAG+f0.len()].copy_from_slice(f0);
+    let sections     = voiced_sections(&contour);
+    let mut smoothed = vec![0.0; f0.len()];
+    for (channel, &(start, end)) in multi_channel_f0(&contour, &sections).into_iter().zip(&sections) {
+        let filtered = filter_f0(channel, start, end);
+        smoothed[start-LAG..=end-LAG].copy_from_slice(&filtered[start..=end]);
+    }
+    smoothed
+}
+
+fn samples_for_harvest(fs: i32, x_length: usize, frame_period: f64) -> usize {
+    (1000.0 * x_length as f64 / fs as f64 / frame_period) as usize + 1
+}
+
+// Harvest at a whole-millisecond frame period.
+fn harvest_general_body(x: &[f64], fs: i32, frame_period: usize, speed: i32, option: &HarvestOption) -> Vec<f64> {
+    let adjusted_f0_floor = option.f0_floor * 0.9;
+    let adjusted_f0_ceil  = option.f0_ceil * 1.1;
+    let channels          = 1 + ((adjusted_f0_ceil / adjusted_f0_floor).ln() / LN_2 * CHANNELS_IN_OCTAVE) as usize;
+    let boundary_f0: Vec<f64> = (0..channels).map(|i| adjusted_f0_floor * 2f64.powf((i + 1) as f64 / CHANNELS_IN_OCTAVE)).collect();
+
+    let decimation_ratio = speed.clamp(1, 12) as usize;
+    let y_length         = (x.len() as f64 / decimation_ratio as f64).ceil() as usize;
+    let actual_fs        = fs as f64 / decimation_ratio as f64;
+    let fft_size         = get_suitable_fft_size(y_length + 5 + 2 * (2.0 * actual_fs / boundary_f0[0]) as usize);
+    let (y, y_spectrum)  = waveform_and_spectrum(x, y_length, fft_size, decimation_ratio);
+
+    let f0_length = samples_for_harvest(fs, x.len(), frame_period as f64);
+    let temporal_positions: Vec<f64> = (0..f0_length).map(|i| (i * frame_period) as f64 / 1000.0).collect();
+
+    let raw_candidates: Vec<Vec<f64>> = boundary_f0.iter()
+        .map(|&boundary| raw_f0_candidate(boundary, actual_fs, &y_spectrum, y_length, fft_size, option, &temporal_positions))
+        .collect();
+    let max_candidates = matlab_round(channels as f64 / 10.0) as usize * OVERLAP;
+    let (mut candidates, number) = official_f0_candidates(&raw_candidates, f0_length, max_candidates);
+    overlap_f0_candidates(&mut candidates, number);
+    let number = number * OVERLAP;
+
+    let mut scores = vec![vec![0.0; max_candidates]; f0_length];
+    for ((frame, frame_scores), &position) in candidates.iter_mut().zip(&mut scores).zip(&temporal_positions) {
+        for (f0, score) in frame[..number].iter_mut().zip(&mut frame_scores[..number]) {
+            let (refined, refined_score) = refined_f0(&y, actual_fs, position, *f0, option);
+            *f0    = refined;
+            *score = refined_score;
+        }
+    }
+    remove_unreliable_candidates(&mut candidates, &mut scores, number);
+    smooth_f0_contour(&fix_f0_contour(&candidates, &scores, number))
+}
+
+/// Pure-Rust Harvest with the same interface as `crate::harvest`. Voicing
+/// decisions match the C implementation and F0 agrees to a relative 1e-9.
+pub fn harvest(x: &[f64], fs: i32, option: &HarvestOption) -> Result<(Vec<f64>, Vec<f64>)> {
+    option.validate(fs)?;
+    check_signal(x)?;
+    let speed = matlab_round(fs as f64 / 8000.0);
+    // Harvest always runs at 1 ms and samples that contour at the requested period.
+    let basic_f0  = harvest_general_body(x, fs, 1, speed, option);
+    let f0_length = samples_for_harvest(fs, x.len(), option.frame_period);
+    let temporal_positions: Vec<f64> = (0..f0_length).map(|i| i as f64 * option.frame_period / 1000.0).collect();
+    let f0 = temporal_positions.iter().map(|&t| basic_f0[(basic_f0.len()-1).min(matlab_round(t * 1000.0) as usize)]).collect();
+    Ok((temporal_positions, f0))
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use crate::{HarvestOption, harvest};
+    use crate::native::test_signals::{vibrato, assert_close};
+
+    // Agreement with the C implementation: identical voicing, F0 within a relative 1e-9.
+    fn compare(fs: i32, option: &HarvestOption) {
+        let x = vibrato(fs, fs as usize);
+        let (reference_positions, reference_f0) = harvest(&x, fs, option).unwrap();
+        let (positions, f0) = super::harvest(&x, fs, option).unwrap();
+        assert_eq!(positions, reference_positions);
+        assert!(reference_f0.iter().any(|&v| v > 0.0));
+        // Unvoiced reference frames allow no error, so voicing must agree.
+        assert_close("f0", &f0, &reference_f0, |r| 1e-9 * r);
+    }
+
+    #[test]
+    fn test_matches_c() {
+        compare(16000, &HarvestOption::new());
+        compare(22050, &HarvestOption::new().frame_period(1.0).f0_floor(100.0).f0_ceil(400.0));
+    }
+
+    #[test]
+    fn test_without_decimation() {
+        compare(8000, &HarvestOption::new().frame_period(2.5));
+    }
+}
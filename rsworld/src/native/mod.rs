@@ -0,0 +1,96 @@
+//! Pure-Rust ports of WORLD's algorithms, enabled by the `native` feature.
+//!
+//! Each function takes the same arguments and options as its FFI counterpart
+//! at the crate root and is tested against it. Results are not bit-identical,
+//! since the FFT rounds differently from WORLD's, but stay within the tolerance
+//! each function documents.
+//!
+//! Without the `ffi` feature WORLD's C++ is not built at all: the crate root
+//! then re-exports these functions, and the comparison tests are skipped.
+
+mod common;
+mod dio;
+mod harvest;
+mod cheaptrick;
+mod d4c;
+mod stonemask;
+mod codec;
+mod synthesis;
+
+pub use dio::dio;
+pub use harvest::harvest;
+pub use cheaptrick::{get_fft_size_for_cheaptrick, cheaptrick, cheaptrick_frame};
+pub use d4c::{d4c, d4c_frame, d4c_love_train};
+pub use stonemask::stonemask;
+pub use codec::{get_number_of_aperiodicities, code_aperiodicity, decode_aperiodicity, code_spectral_envelope, decode_spectral_envelope};
+pub use synthesis::{synthesis, synthesis_with_length, synthesis_with_seed};
+
+/// Fixtures shared by the tests that compare the ports with WORLD's C code.
+#[cfg(all(test, feature = "ffi"))]
+pub(crate) mod test_signals {
+    use std::f64::consts::PI;
+
+    use crate::{HarvestOption, CheapTrickOption, D4COption, Spectrogram, Aperiodicity, harvest, cheaptrick, d4c};
+
+    /// 140 +- 40 Hz vibrato at 1.5 Hz, voiced in the middle half of every second.
+    pub(crate) fn vibrato(fs: i32, length: usize) -> Vec<f64> {
+        (0..length).map(|i| {
+            let t     = i as f64 / fs as f64;
+            let phase = 2.0 * PI * 140.0 * t + 40.0 / 1.5 * (1.0 - (2.0 * PI * 1.5 * t).cos());
+            let gate  = if (0.25..0.75).contains(&(t % 1.0)) { 1.0 } else { 0.0 };
+            gate * (1..=10).map(|k| 0.1 / k as f64 * (k as f64 * phase).sin()).sum::<f64>() + 1e-4 * ((i * 7919 % 1000) as f64 / 1000.0 - 0.5)
+        }).collect()
+    }
+
+    /// `vibrato` with noise of rising level, so the aperiodicity covers its range.
+    pub(crate) fn breathy_vibrato(fs: i32, length: usize) -> Vec<f64> {
+        let mut seed: u32 = 1;
+        (0..length).map(|i| {
+            let t     = i as f64 / fs as f64;
+            let phase = 2.0 * PI * 140.0 * t + 40.0 / 1.5 * (1.0 - (2.0 * PI * 1.5 * t).cos());
+            let gate  = if (0.25..0.75).contains(&(t % 1.0)) { 1.0 } else { 0.0 };
+            seed      = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let noise = seed as f64 / u32::MAX as f64 - 0.5;
+            gate * ((1..=10).map(|k| 0.1 / k as f64 * (k as f64 * phase).sin()).sum::<f64>() + 0.05 * t * noise) + 1e-4 * noise
+        }).collect()
+    }
+
+    /// WORLD's `test/vaiueo2d.wav`, a recorded /vaiueo/ at 22.05 kHz, and its sample rate.
+    pub(crate) fn vaiueo2d() -> (Vec<f64>, i32) {
+        let bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/vaiueo2d.wav"));
+        let le32  = |at: usize| u32::from_le_bytes([bytes[at], bytes[at+1], bytes[at+2], bytes[at+3]]) as usize;
+        // 16-bit mono PCM: walk the chunks after "RIFF" size "WAVE".
+        let (mut fs, mut x, mut at) = (0, Vec::new(), 12);
+        while at + 8 <= bytes.len() {
+            let size = le32(at+4);
+            match &bytes[at..at+4] {
+                b"fmt " => fs = le32(at+12) as i32,
+                b"data" => x  = bytes[at+8..at+8+size].chunks_exact(2).map(|v| i16::from_le_bytes([v[0], v[1]]) as f64 / 32768.0).collect(),
+                _       => {},
+            }
+            at += 8 + size + size % 2;
+        }
+        (x, fs)
+    }
+
+    /// Harvest, CheapTrick and D4C of `x` with default options, through the C code.
+    pub(crate) fn analyse(x: &[f64], fs: i32) -> (Vec<f64>, Spectrogram, Aperiodicity) {
+        let (temporal_positions, f0) = harvest(x, fs, &HarvestOption::new()).unwrap();
+        let spectrogram  = cheaptrick(x, fs, &temporal_positions, &f0, &CheapTrickOption::new()).unwrap();
+        let aperiodicity = d4c(x, fs, &temporal_positions, &f0, spectrogram.fft_size() as i32, &D4COption::new()).unwrap();
+        (f0, spectrogram, aperiodicity)
+    }
+
+    pub(crate) fn peak(values: &[f64]) -> f64 {
+        values.iter().fold(0.0, |peak: f64, v| peak.max(v.abs()))
+    }
+
+    /// Compares `values` with the C `reference` element by element, allowing
+    /// `tolerance` of each reference value.
+    pub(crate) fn assert_close(name: &str, values: &[f64], reference: &[f64], tolerance: impl Fn(f64) -> f64) {
+        assert_eq!(values.len(), reference.len(), "{}", name);
+        for (i, (v, r)) in values.iter().zip(reference).enumerate() {
+            assert!((v - r).abs() <= tolerance(*r), "{}[{}]: {} != {}", name, i, v, r);
+        }
+    }
+}
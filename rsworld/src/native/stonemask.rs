@@ -0,0 +1,115 @@
+use std::f64::consts::{PI, LN_2};
+
+use crate::check_f0;
+use crate::error::{Result, check_fs, check_signal};
+use super::common::{SAFE_GUARD_MINIMUM, matlab_round, forward_real_fft};
+
+const FLOOR_F0: f64 = 40.0;
+
+// Weighted mean of the instantaneous frequencies at the first harmonics,
+// divided by their harmonic numbers. Harmonics past the spectrum, which WORLD
+// reads out of bounds, count as silent.
+fn fix_f0(power_spectrum: &[f64], numerator_i: &[f64], fft_size: usize, fs: i32, initial_f0: f64, number_of_harmonics: usize) -> f64 {
+    let mut numerator   = 0.0;
+    let mut denominator = 0.0;
+    for i in 0..number_of_harmonics {
+        let index = matlab_round(initial_f0 * fft_size as f64 / fs as f64 * (i + 1) as f64) as usize;
+        let power = power_spectrum.get(index).cloned().unwrap_or(0.0);
+        let instantaneous_frequency = if power == 0.0 {
+            0.0
+        } else {
+            index as f64 * fs as f64 / fft_size as f64 + numerator_i[index] / power * fs as f64 / 2.0 / PI
+        };
+        numerator   += power.sqrt() * instantaneous_frequency;
+        denominator += power.sqrt() * (i + 1) as f64;
+    }
+    numerator / (denominator + SAFE_GUARD_MINIMUM)
+}
+
+fn tentative_f0(power_spectrum: &[f64], numerator_i: &[f64], fft_size: usize, fs: i32, initial_f0: f64) -> f64 {
+    let tentative_f0 = fix_f0(power_spectrum, numerator_i, fft_size, fs, initial_f0, 2);
+    // Too large a correction is rejected.
+    if tentative_f0 <= 0.0 || tentative_f0 > initial_f0 * 2.0 {
+        return 0.0;
+    }
+    fix_f0(power_spectrum, numerator_i, fft_size, fs, tentative_f0, 6)
+}
+
+// Instantaneous frequency from the spectra under a Blackman window and its
+// derivative.
+fn mean_f0(x: &[f64], fs: i32, current_position: f64, initial_f0: f64, fft_size: usize, half_window_length: isize) -> f64 {
+    let window_length_in_time = (2.0 * half_window_length as f64 + 1.0) / fs as f64;
+    let index_raw: Vec<i32> = (-half_window_length..=half_window_length)
+        .map(|i| matlab_round((current_position + i as f64 / fs as f64) * fs as f64))
+        .collect();
+    let main_window: Vec<f64> = index_raw.iter().map(|&index| {
+        let t = (index as f64 - 1.0) / fs as f64 - current_position;
+        0.42 + 0.5 * (2.0 * PI * t / window_length_in_time).cos() + 0.08 * (4.0 * PI * t / window_length_in_time).cos()
+    }).collect();
+    let length          = main_window.len();
+    let mut diff_window = vec![0.0; length];
+    diff_window[0] = -main_window[1] / 2.0;
+    for i in 1..length-1 {
+        diff_window[i] = -(main_window[i+1] - main_window[i-1]) / 2.0;
+    }
+    diff_window[length-1] = main_window[length-2] / 2.0;
+
+    let spectrum = |window: &[f64]| {
+        let mut waveform = vec![0.0; fft_size];
+        for ((out, &index), w) in waveform.iter_mut().zip(&index_raw).zip(window) {
+            *out = x[(index - 1).clamp(0, x.len() as i32 - 1) as usize] * w;
+        }
+        forward_real_fft(&waveform)
+    };
+    let main_spectrum = spectrum(&main_window);
+    let diff_spectrum = spectrum(&diff_window);
+    let numerator_i: Vec<f64>    = main_spectrum.iter().zip(&diff_spectrum).map(|(m, d)| m.re * d.im - m.im * d.re).collect();
+    let power_spectrum: Vec<f64> = main_spectrum.iter().map(|m| m.re * m.re + m.im * m.im).collect();
+    tentative_f0(&power_spectrum, &numerator_i, fft_size, fs, initial_f0)
+}
+
+fn refined_f0(x: &[f64], fs: i32, current_position: f64, initial_f0: f64) -> f64 {
+    if initial_f0 <= FLOOR_F0 || initial_f0 > fs as f64 / 12.0 {
+        return 0.0;
+    }
+    let half_window_length = (1.5 * fs as f64 / initial_f0 + 1.0) as isize;
+    let fft_size = 2f64.powf(2.0 + ((half_window_length as f64 * 2.0 + 1.0).ln() / LN_2) as i32 as f64) as usize;
+    let mean_f0  = mean_f0(x, fs, current_position, initial_f0, fft_size, half_window_length);
+    // Corrections over 20 % keep the initial F0.
+    if (mean_f0 - initial_f0).abs() / initial_f0 > 0.2 { initial_f0 } else { mean_f0 }
+}
+
+/// Pure-Rust StoneMask with the same interface as `crate::stonemask`. F0
+/// agrees with the C implementation to a relative 1e-9.
+pub fn stonemask(x: &[f64], fs: i32, temporal_positions: &[f64], f0: &[f64]) -> Result<Vec<f64>> {
+    check_fs(fs)?;
+    check_signal(x)?;
+    check_f0(temporal_positions, f0)?;
+    Ok(temporal_positions.iter().zip(f0).map(|(&position, &f0)| refined_f0(x, fs, position, f0)).collect())
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use crate::{DioOption, dio, stonemask};
+    use crate::native::test_signals::{vibrato, assert_close};
+
+    #[test]
+    fn test_matches_c() {
+        for &fs in &[16000, 44100] {
+            let x = vibrato(fs, fs as usize * 3 / 2);
+            let (temporal_positions, f0) = dio(&x, fs, &DioOption::new()).unwrap();
+            let reference  = stonemask(&x, fs, &temporal_positions, &f0).unwrap();
+            let refined_f0 = super::stonemask(&x, fs, &temporal_positions, &f0).unwrap();
+            assert!(reference.iter().any(|&v| v > 0.0));
+            assert_close("f0", &refined_f0, &reference, |r| 1e-9 * r);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        // At or below 40 Hz, above fs/12 and unvoiced frames come out unvoiced.
+        let fs = 16000;
+        let x  = vibrato(fs, fs as usize);
+        assert_eq!(super::stonemask(&x, fs, &[0.5, 0.5, 0.5], &[0.0, 40.0, 1400.0]).unwrap(), vec![0.0; 3]);
+    }
+}
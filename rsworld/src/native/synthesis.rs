@@ -0,0 +1,244 @@
+use std::f64::consts::PI;
+
+use crate::{Spectrogram, Aperiodicity, get_samples_for_synthesis, check_synthesis};
+use crate::fft::Complex;
+use crate::error::{Result, check_frame_period};
+use super::common::{SAFE_GUARD_MINIMUM, interp1, Randn, forward_real_fft, inverse_real_fft, minimum_phase_spectrum};
+
+const DEFAULT_F0: f64 = 500.0;
+
+fn fftshift(x: &[f64]) -> Vec<f64> {
+    let half = x.len()/2;
+    x[half..].iter().chain(&x[..half]).cloned().collect()
+}
+
+fn safe_aperiodicity(x: f64) -> f64 {
+    x.clamp(0.001, 1.0 - SAFE_GUARD_MINIMUM)
+}
+
+// Spectrum of `noise_size` samples of zero-mean white noise.
+fn noise_spectrum(noise_size: usize, fft_size: usize, randn: &mut Randn) -> Vec<Complex> {
+    let mut waveform = vec![0.0; fft_size];
+    waveform[..noise_size].iter_mut().for_each(|v| *v = randn.next());
+    let average = waveform[..noise_size].iter().sum::<f64>() / noise_size as f64;
+    waveform[..noise_size].iter_mut().for_each(|v| *v -= average);
+    forward_real_fft(&waveform)
+}
+
+fn aperiodic_response(noise_size: usize, fft_size: usize, spectrum: &[f64], aperiodic_ratio: &[f64], current_vuv: f64,
+                      randn: &mut Randn) -> Vec<f64> {
+    let noise = noise_spectrum(noise_size, fft_size, randn);
+    let log_spectrum: Vec<f64> = if current_vuv != 0.0 {
+        spectrum.iter().zip(aperiodic_ratio).map(|(s, a)| (s * a).ln() / 2.0).collect()
+    } else {
+        spectrum.iter().map(|s| s.ln() / 2.0).collect()
+    };
+    let response: Vec<Complex> = minimum_phase_spectrum(&log_spectrum, fft_size).iter().zip(&noise).map(|(&m, &n)| m * n).collect();
+    fftshift(&inverse_real_fft(&response, fft_size))
+}
+
+// Removes the DC component of the second half of `periodic_response`, spread
+// out with the Hann window `dc_remover`.
+fn remove_dc_component(periodic_response: &mut [f64], dc_remover: &[f64]) {
+    let fft_size     = periodic_response.len();
+    let dc_component = periodic_response[fft_size/2..].iter().sum::<f64>();
+    for (i, (v, d)) in periodic_response.iter_mut().zip(dc_remover).enumerate() {
+        *v = if i < fft_size/2 { -dc_component * d } else { *v - dc_component * d };
+    }
+}
+
+fn periodic_response(fft_size: usize, spectrum: &[f64], aperiodic_ratio: &[f64], current_vuv: f64, dc_remover: &[f64],
+                     fractional_time_shift: f64, fs: i32) -> Vec<f64> {
+    if current_vuv <= 0.5 || aperiodic_ratio[0] > 0.999 {
+        return vec![0.0; fft_size];
+    }
+    let log_spectrum: Vec<f64> = spectrum.iter().zip(aperiodic_ratio).map(|(s, a)| (s * (1.0 - a) + SAFE_GUARD_MINIMUM).ln() / 2.0).collect();
+    // Delays the response by the fraction of a sample the pulse falls between
+    // samples. WORLD takes the sine as sqrt(1 - cos^2), so its sign is dropped.
+    let coefficient = 2.0 * PI * fractional_time_shift * fs as f64 / fft_size as f64;
+    let shifted: Vec<Complex> = minimum_phase_spectrum(&log_spectrum, fft_size).iter().enumerate().map(|(i, v)| {
+        let re2 = (coefficient * i as f64).cos();
+        let im2 = (1.0 - re2 * re2).sqrt();
+        Complex::new(v.re * re2 + v.im * im2, v.im * re2 - v.re * im2)
+    }).collect();
+    let mut response = fftshift(&inverse_real_fft(&shifted, fft_size));
+    remove_dc_component(&mut response, dc_remover);
+    response
+}
+
+// Linear interpolation between the two frames around `current_time`.
+fn interpolate_frames(current_time: f64, frame_period: f64, frames: usize, row: impl Fn(usize) -> Vec<f64>) -> Vec<f64> {
+    let floor         = (frames - 1).min((current_time / frame_period).floor() as usize);
+    let ceil          = (frames - 1).min((current_time / frame_period).ceil() as usize);
+    let interpolation = current_time / frame_period - floor as f64;
+    if floor == ceil {
+        return row(floor);
+    }
+    row(floor).iter().zip(&row(ceil)).map(|(a, b)| (1.0 - interpolation) * a + interpolation * b).collect()
+}
+
+// Pulse and noise response at one pulse, `fft_size` samples.
+fn one_frame_segment(pulse: &Pulse, noise_size: usize, spectral_envelope: &[f64], aperiodic_ratio: &[f64], fs: i32, dc_remover: &[f64],
+                     randn: &mut Randn) -> Vec<f64> {
+    let fft_size  = dc_remover.len();
+    let periodic  = periodic_response(fft_size, spectral_envelope, aperiodic_ratio, pulse.vuv, dc_remover, pulse.time_shift, fs);
+    let aperiodic = aperiodic_response(noise_size, fft_size, spectral_envelope, aperiodic_ratio, pulse.vuv, randn);
+    let sqrt_noise_size = (noise_size as f64).sqrt();
+    periodic.iter().zip(&aperiodic).map(|(p, a)| (p * sqrt_noise_size + a) / fft_size as f64).collect()
+}
+
+struct Pulse {
+    index: usize,
+    time: f64,
+    // Seconds from sample `index` to where the phase crosses 2 pi.
+    time_shift: f64,
+    vuv: f64,
+}
+
+// Pulses one F0 period apart, with voicing interpolated between frames and
+// 500 Hz pulses where unvoiced.
+fn time_base(f0: &[f64], fs: i32, frame_period: f64, y_length: usize, lowest_f0: f64) -> Vec<Pulse> {
+    let f0_length = f0.len();
+    let time_axis: Vec<f64> = (0..y_length).map(|i| i as f64 / fs as f64).collect();
+    let mut coarse_time_axis: Vec<f64> = (0..=f0_length).map(|i| i as f64 * frame_period).collect();
+    let mut coarse_f0: Vec<f64>  = f0.iter().map(|&v| if v < lowest_f0 { 0.0 } else { v }).collect();
+    let mut coarse_vuv: Vec<f64> = coarse_f0.iter().map(|&v| if v == 0.0 { 0.0 } else { 1.0 }).collect();
+    coarse_f0.push(coarse_f0[f0_length-1] * 2.0 - coarse_f0[f0_length-2]);
+    coarse_vuv.push(coarse_vuv[f0_length-1] * 2.0 - coarse_vuv[f0_length-2]);
+    coarse_time_axis[f0_length] = f0_length as f64 * frame_period;
+
+    let vuv: Vec<f64> = interp1(&coarse_time_axis, &coarse_vuv, &time_axis).iter().map(|&v| if v > 0.5 { 1.0 } else { 0.0 }).collect();
+    let interpolated_f0: Vec<f64> = interp1(&coarse_time_axis, &coarse_f0, &time_axis).iter().zip(&vuv)
+        .map(|(&f0, &vuv)| if vuv == 0.0 { DEFAULT_F0 } else { f0 })
+        .collect();
+
+    let mut wrap_phase  = Vec::with_capacity(y_length);
+    let mut total_phase = 0.0;
+    for f0 in &interpolated_f0 {
+        total_phase += 2.0 * PI * f0 / fs as f64;
+        wrap_phase.push(total_phase % (2.0 * PI));
+    }
+    (0..y_length.saturating_sub(1)).filter(|&i| (wrap_phase[i+1] - wrap_phase[i]).abs() > PI).map(|i| {
+        let y1 = wrap_phase[i] - 2.0 * PI;
+        let y2 = wrap_phase[i+1];
+        Pulse { index: i, time: time_axis[i], time_shift: -y1 / (y2 - y1) / fs as f64, vuv: vuv[i] }
+    }).collect()
+}
+
+// Hann window of unit sum, symmetric about the centre of `fft_size` samples.
+fn dc_remover(fft_size: usize) -> Vec<f64> {
+    let mut dc_remover = vec![0.0; fft_size];
+    for (i, v) in dc_remover[..fft_size/2].iter_mut().enumerate() {
+        *v = 0.5 - 0.5 * (2.0 * PI * (i as f64 + 1.0) / (1.0 + fft_size as f64)).cos();
+    }
+    let dc_component = dc_remover[..fft_size/2].iter().sum::<f64>() * 2.0;
+    for i in 0..fft_size/2 {
+        dc_remover[i] /= dc_component;
+        dc_remover[fft_size-i-1] = dc_remover[i];
+    }
+    dc_remover
+}
+
+/// Pure-Rust synthesis with the same interface as `crate::synthesis`.
+pub fn synthesis(f0: &[f64], spectrogram: &Spectrogram, aperiodicity: &Aperiodicity, frame_period: f64, fs: i32) -> Result<Vec<f64>> {
+    check_frame_period(frame_period)?;
+    synthesis_with_length(f0, spectrogram, aperiodicity, frame_period, fs, get_samples_for_synthesis(f0.len(), frame_period, fs))
+}
+
+/// Pure-Rust synthesis with the same interface as `crate::synthesis_with_length`.
+/// Samples agree with the C implementation to 1e-9 of the waveform's peak.
+pub fn synthesis_with_length(f0: &[f64], spectrogram: &Spectrogram, aperiodicity: &Aperiodicity, frame_period: f64, fs: i32,
+                             y_length: usize) -> Result<Vec<f64>> {
+    synthesis_with_seed(f0, spectrogram, aperiodicity, frame_period, fs, y_length, 0)
+}
+
+/// `synthesis_with_length` with the aperiodic component drawn from a noise
+/// sequence chosen by `seed`. Seed 0 is WORLD's sequence, so it gives the same
+/// waveform as the C implementation; the periodic component does not depend
+/// on the seed.
+pub fn synthesis_with_seed(f0: &[f64], spectrogram: &Spectrogram, aperiodicity: &Aperiodicity, frame_period: f64, fs: i32,
+                           y_length: usize, seed: u64) -> Result<Vec<f64>> {
+    check_synthesis(f0, spectrogram, aperiodicity, frame_period, fs)?;
+    let fft_size = spectrogram.fft_size();
+    let mut y    = vec![0.0; y_length];
+    if y_length == 0 {
+        return Ok(y);
+    }
+    let frame_period = frame_period / 1000.0;
+    // WORLD divides in integers here.
+    let lowest_f0    = (fs / fft_size as i32) as f64 + 1.0;
+    let pulses       = time_base(f0, fs, frame_period, y_length, lowest_f0);
+    let dc_remover   = dc_remover(fft_size);
+    let mut randn    = Randn::with_seed(seed);
+    for (i, pulse) in pulses.iter().enumerate() {
+        let noise_size = pulses[(i+1).min(pulses.len()-1)].index - pulse.index;
+        let spectral_envelope = interpolate_frames(pulse.time, frame_period, f0.len(), |i| spectrogram.row(i).iter().map(|v| v.abs()).collect());
+        let aperiodic_ratio: Vec<f64> = interpolate_frames(pulse.time, frame_period, f0.len(),
+            |i| aperiodicity.row(i).iter().map(|&v| safe_aperiodicity(v)).collect()).iter().map(|v| v.powi(2)).collect();
+        let response = one_frame_segment(pulse, noise_size, &spectral_envelope, &aperiodic_ratio, fs, &dc_remover, &mut randn);
+        let offset = pulse.index as isize - fft_size as isize / 2 + 1;
+        let lower  = 0.max(-offset) as usize;
+        let upper  = (fft_size as isize).min(y_length as isize - offset).max(0) as usize;
+        for j in lower..upper {
+            y[(j as isize + offset) as usize] += response[j];
+        }
+    }
+    Ok(y)
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use crate::{HarvestOption, CheapTrickOption, D4COption, Spectrogram, Aperiodicity, synthesis, synthesis_with_length};
+    use crate::native::test_signals::{self, vibrato, peak};
+
+    fn analyse(fs: i32) -> (Vec<f64>, Spectrogram, Aperiodicity) {
+        test_signals::analyse(&vibrato(fs, fs as usize * 3 / 2), fs)
+    }
+
+    // Within 1e-9 of the peak of the C output.
+    fn assert_close(y: &[f64], reference: &[f64]) {
+        let peak = peak(reference);
+        test_signals::assert_close("y", y, reference, |_| 1e-9 * peak);
+    }
+
+    #[test]
+    fn test_matches_c() {
+        for &(fs, frame_period) in &[(16000, 5.0), (44100, 5.0)] {
+            let (f0, spectrogram, aperiodicity) = analyse(fs);
+            assert_close(&super::synthesis(&f0, &spectrogram, &aperiodicity, frame_period, fs).unwrap(),
+                         &synthesis(&f0, &spectrogram, &aperiodicity, frame_period, fs).unwrap());
+        }
+        // Resampling the frames at another frame period, and a length past the last frame.
+        let (f0, spectrogram, aperiodicity) = analyse(16000);
+        assert_close(&super::synthesis_with_length(&f0, &spectrogram, &aperiodicity, 3.7, 16000, 30000).unwrap(),
+                     &synthesis_with_length(&f0, &spectrogram, &aperiodicity, 3.7, 16000, 30000).unwrap());
+    }
+
+    // Harvest, CheapTrick, D4C and synthesis all in Rust against the same chain in C.
+    #[test]
+    fn test_native_loop() {
+        let fs = 16000;
+        let x  = vibrato(fs, fs as usize * 3 / 2);
+        let (temporal_positions, f0) = crate::native::harvest(&x, fs, &HarvestOption::new()).unwrap();
+        let spectrogram  = crate::native::cheaptrick(&x, fs, &temporal_positions, &f0, &CheapTrickOption::new()).unwrap();
+        let aperiodicity = crate::native::d4c(&x, fs, &temporal_positions, &f0, spectrogram.fft_size() as i32, &D4COption::new()).unwrap();
+        let (reference_f0, reference_spectrogram, reference_aperiodicity) = analyse(fs);
+        assert_close(&super::synthesis(&f0, &spectrogram, &aperiodicity, 5.0, fs).unwrap(),
+                     &synthesis(&reference_f0, &reference_spectrogram, &reference_aperiodicity, 5.0, fs).unwrap());
+    }
+
+    #[test]
+    fn test_seed() {
+        let (f0, spectrogram, aperiodicity) = analyse(16000);
+        let length    = f0.len() * 80;
+        let reference = super::synthesis_with_length(&f0, &spectrogram, &aperiodicity, 5.0, 16000, length).unwrap();
+        assert_eq!(super::synthesis_with_seed(&f0, &spectrogram, &aperiodicity, 5.0, 16000, length, 0).unwrap(), reference);
+        let seeded = super::synthesis_with_seed(&f0, &spectrogram, &aperiodicity, 5.0, 16000, length, 42).unwrap();
+        assert_eq!(super::synthesis_with_seed(&f0, &spectrogram, &aperiodicity, 5.0, 16000, length, 42).unwrap(), seeded);
+        assert_ne!(seeded, reference);
+        // Only the noise changes: the two stay close where the signal is voiced.
+        let energy     = reference.iter().map(|v| v * v).sum::<f64>();
+        let difference = seeded.iter().zip(&reference).map(|(a, b)| (a - b) * (a - b)).sum::<f64>();
+        assert!(difference < 0.1 * energy);
+    }
+}
@@ -0,0 +1,283 @@
+use std::io::{Read, Seek, Write};
+
+use npyz::{AutoSerialize, DType, NpyFile, Order, TypeChar, WriteOptions, WriterBuilder};
+use npyz::npz::{NpzArchive, file_name_from_array_name};
+use npyz::zip::ZipWriter;
+use npyz::zip::write::FileOptions;
+
+use crate::{
+    WorldParameters,
+    Spectrogram,
+    Aperiodicity,
+    CodedSpectrogram,
+    CodedAperiodicity,
+    MelCepstrum,
+    MelSpectrogram,
+    Mfcc,
+    code_spectral_envelope,
+    decode_spectral_envelope,
+    code_aperiodicity,
+    decode_aperiodicity,
+};
+use crate::error::{WorldError, Result, check_same_length};
+
+fn from_zip(error: npyz::zip::result::ZipError) -> WorldError {
+    match error {
+        npyz::zip::result::ZipError::Io(error) => error.into(),
+        error                                  => WorldError::InvalidFormat(error.to_string()),
+    }
+}
+
+fn write_array<W: Write>(writer: W, shape: &[u64], values: &[f64]) -> Result<()> {
+    let mut writer = WriteOptions::new().default_dtype().shape(shape).writer(writer).begin_nd()?;
+    writer.extend(values.iter().copied())?;
+    writer.finish()?;
+    Ok(())
+}
+
+fn write_scalar<W: Write, T: AutoSerialize>(writer: W, value: T) -> Result<()> {
+    let mut writer = WriteOptions::new().default_dtype().shape(&[]).writer(writer).begin_nd()?;
+    writer.push(&value)?;
+    writer.finish()?;
+    Ok(())
+}
+
+// Reads any float or integer array as f64 in C order, returning the shape alongside.
+fn read_array<R: Read>(npy: NpyFile<R>) -> Result<(Vec<usize>, Vec<f64>)> {
+    let shape: Vec<usize> = npy.shape().iter().map(|&n| n as usize).collect();
+    let order = npy.order();
+    let values: Vec<f64> = match npy.dtype() {
+        DType::Plain(ty) => match (ty.type_char(), ty.size_field()) {
+            (TypeChar::Float, 8) => npy.into_vec::<f64>()?,
+            (TypeChar::Float, 4) => npy.into_vec::<f32>()?.into_iter().map(f64::from).collect(),
+            (TypeChar::Int,   8) => npy.into_vec::<i64>()?.into_iter().map(|v| v as f64).collect(),
+            (TypeChar::Int,   4) => npy.into_vec::<i32>()?.into_iter().map(f64::from).collect(),
+            _                    => return Err(WorldError::InvalidFormat(format!("unsupported dtype {}", ty))),
+        },
+        dtype => return Err(WorldError::InvalidFormat(format!("unsupported dtype {}", dtype.descr()))),
+    };
+    if order == Order::Fortran && shape.len() == 2 {
+        let (frames, columns) = (shape[0], shape[1]);
+        let transposed = (0..frames*columns).map(|i| values[(i % columns)*frames + i / columns]).collect();
+        return Ok((shape, transposed));
+    }
+    Ok((shape, values))
+}
+
+fn read_vector<R: Read>(npy: NpyFile<R>) -> Result<Vec<f64>> {
+    let (shape, values) = read_array(npy)?;
+    if shape.len() != 1 {
+        return Err(WorldError::InvalidFormat(format!("expected a 1-d array, found shape {:?}", shape)));
+    }
+    Ok(values)
+}
+
+fn read_matrix<R: Read>(npy: NpyFile<R>) -> Result<(usize, usize, Vec<f64>)> {
+    let (shape, values) = read_array(npy)?;
+    if shape.len() != 2 {
+        return Err(WorldError::InvalidFormat(format!("expected a 2-d array, found shape {:?}", shape)));
+    }
+    Ok((shape[0], shape[1], values))
+}
+
+fn read_scalar<R: Read>(npy: NpyFile<R>) -> Result<f64> {
+    let (shape, values) = read_array(npy)?;
+    match values[..] {
+        [value] => Ok(value),
+        _       => Err(WorldError::InvalidFormat(format!("expected a scalar, found shape {:?}", shape))),
+    }
+}
+
+/// Writes a 1-d float64 array such as f0 or temporal positions.
+pub fn write_npy_vector<W: Write>(writer: W, values: &[f64]) -> Result<()> {
+    write_array(writer, &[values.len() as u64], values)
+}
+
+/// Reads a 1-d float32 or float64 array.
+pub fn read_npy_vector<R: Read>(reader: R) -> Result<Vec<f64>> {
+    read_vector(NpyFile::new(reader)?)
+}
+
+macro_rules! npy_matrix {
+    ($name:ident) => {
+        impl $name {
+            /// Writes a `(frames, columns)` float64 array, the layout pyworld uses.
+            pub fn write_npy<W: Write>(&self, writer: W) -> Result<()> {
+                write_array(writer, &[self.frames() as u64, self.columns() as u64], self.as_slice())
+            }
+
+            pub fn read_npy<R: Read>(reader: R) -> Result<Self> {
+                let (frames, columns, values) = read_matrix(NpyFile::new(reader)?)?;
+                Self::from_vec(frames, columns, values)
+            }
+        }
+    };
+}
+
+npy_matrix!(Spectrogram);
+npy_matrix!(Aperiodicity);
+npy_matrix!(CodedSpectrogram);
+npy_matrix!(CodedAperiodicity);
+npy_matrix!(MelCepstrum);
+npy_matrix!(MelSpectrogram);
+npy_matrix!(Mfcc);
+
+/// Writes `f0`, `temporal_positions`, `sp`, `ap`, `fs`, `frame_period`, `fft_size`
+/// and `signal_length` into one archive.
+///
+/// With `coded_dimensions` the envelope is stored as `coded_sp` with that many
+/// dimensions and the aperiodicity as band aperiodicity `coded_ap`.
+pub fn write_npz<W: Write + Seek>(writer: W, parameters: &WorldParameters, coded_dimensions: Option<i32>) -> Result<()> {
+    let fs      = parameters.fs();
+    let mut zip = ZipWriter::new(writer);
+    write_npy_vector(start(&mut zip, "f0")?,                 parameters.f0())?;
+    write_npy_vector(start(&mut zip, "temporal_positions")?, parameters.temporal_positions())?;
+    match coded_dimensions {
+        Some(dimensions) => {
+            code_spectral_envelope(parameters.spectrogram(), fs, dimensions)?.write_npy(start(&mut zip, "coded_sp")?)?;
+            code_aperiodicity(parameters.aperiodicity(), fs)?.write_npy(start(&mut zip, "coded_ap")?)?;
+        },
+        None => {
+            parameters.spectrogram().write_npy(start(&mut zip, "sp")?)?;
+            parameters.aperiodicity().write_npy(start(&mut zip, "ap")?)?;
+        },
+    }
+    write_scalar(start(&mut zip, "fs")?,            fs as i64)?;
+    write_scalar(start(&mut zip, "fft_size")?,      parameters.fft_size() as i64)?;
+    write_scalar(start(&mut zip, "frame_period")?,  parameters.frame_period())?;
+    write_scalar(start(&mut zip, "signal_length")?, parameters.signal_length() as i64)?;
+    zip.finish().map_err(from_zip)?;
+    Ok(())
+}
+
+fn start<'a, W: Write + Seek>(zip: &'a mut ZipWriter<W>, name: &str) -> Result<&'a mut ZipWriter<W>> {
+    zip.start_file(file_name_from_array_name(name), FileOptions::default()).map_err(from_zip)?;
+    Ok(zip)
+}
+
+/// Reads an archive written by `write_npz`, decoding coded parameters. Archives
+/// without `signal_length`, e.g. from pyworld, get the length `synthesis` uses.
+pub fn read_npz<R: Read + Seek>(reader: R) -> Result<WorldParameters> {
+    let mut npz      = NpzArchive::new(reader)?;
+    let fs           = read_scalar(entry(&mut npz, "fs")?)? as i32;
+    let frame_period = read_scalar(entry(&mut npz, "frame_period")?)?;
+    let fft_size     = read_scalar(entry(&mut npz, "fft_size")?)? as i32;
+    let f0           = read_vector(entry(&mut npz, "f0")?)?;
+    let temporal_positions = read_vector(entry(&mut npz, "temporal_positions")?)?;
+    let coded = npz.array_names().any(|name| name == "coded_sp");
+    let (spectrogram, aperiodicity) = if coded {
+        let (frames, columns, values) = read_matrix(entry(&mut npz, "coded_sp")?)?;
+        let spectrogram               = decode_spectral_envelope(&CodedSpectrogram::from_vec(frames, columns, values)?, fs, fft_size)?;
+        let (frames, columns, values) = read_matrix(entry(&mut npz, "coded_ap")?)?;
+        let aperiodicity              = decode_aperiodicity(&CodedAperiodicity::from_vec(frames, columns, values)?, fs, fft_size)?;
+        (spectrogram, aperiodicity)
+    } else {
+        let (frames, columns, values) = read_matrix(entry(&mut npz, "sp")?)?;
+        let spectrogram               = Spectrogram::from_vec(frames, columns, values)?;
+        let (frames, columns, values) = read_matrix(entry(&mut npz, "ap")?)?;
+        let aperiodicity              = Aperiodicity::from_vec(frames, columns, values)?;
+        (spectrogram, aperiodicity)
+    };
+    check_same_length("sp bins", fft_size as usize/2+1, spectrogram.bins())?;
+    let parameters = WorldParameters::new(f0, temporal_positions, spectrogram, aperiodicity, fs, frame_period)?;
+    if !npz.array_names().any(|name| name == "signal_length") {
+        return Ok(parameters);
+    }
+    let signal_length = read_scalar(entry(&mut npz, "signal_length")?)?;
+    if !(0.0..=i32::MAX as f64).contains(&signal_length) {
+        return Err(WorldError::InvalidFormat(format!("invalid signal_length {}", signal_length)));
+    }
+    Ok(parameters.with_signal_length(signal_length as usize))
+}
+
+fn entry<'a, R: Read + Seek>(npz: &'a mut NpzArchive<R>, name: &str) -> Result<NpyFile<npyz::zip::read::ZipFile<'a>>> {
+    npz.by_name(name)?.ok_or_else(|| WorldError::InvalidFormat(format!("missing array {:?}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use npyz::{WriteOptions, WriterBuilder, Order};
+
+    use crate::{Spectrogram, Aperiodicity, CodedSpectrogram, WorldParameters, WorldError, code_aperiodicity, decode_aperiodicity, read_npy_vector, write_npy_vector, read_npz, write_npz};
+
+    fn parameters() -> WorldParameters {
+        let mut spectrogram  = Spectrogram::with_fft_size(3, 1024);
+        let mut aperiodicity = Aperiodicity::with_fft_size(3, 1024);
+        spectrogram.as_mut_slice().iter_mut().enumerate().for_each(|(i, v)| *v = 1e-3 / (1.0 + (i % 513) as f64));
+        aperiodicity.as_mut_slice().iter_mut().for_each(|v| *v = 0.25);
+        WorldParameters::new(vec![0.0, 120.0, 121.0], vec![0.0, 0.005, 0.01], spectrogram, aperiodicity, 16000, 5.0).unwrap()
+    }
+
+    #[test]
+    fn test_npy_shapes() {
+        let mut bytes = Vec::new();
+        Spectrogram::with_fft_size(2, 8).write_npy(&mut bytes).unwrap();
+        let npy = npyz::NpyFile::new(&bytes[..]).unwrap();
+        assert_eq!(npy.shape(), &[2, 5]);
+        assert_eq!(npy.header().dtype().descr(), "'<f8'");
+        assert_eq!(Spectrogram::read_npy(&bytes[..]), Ok(Spectrogram::with_fft_size(2, 8)));
+
+        let mut bytes = Vec::new();
+        write_npy_vector(&mut bytes, &[1.0, 2.0]).unwrap();
+        assert_eq!(npyz::NpyFile::new(&bytes[..]).unwrap().shape(), &[2]);
+        assert_eq!(read_npy_vector(&bytes[..]), Ok(vec![1.0, 2.0]));
+        assert!(matches!(CodedSpectrogram::read_npy(&bytes[..]), Err(WorldError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_npy_float32_fortran() {
+        let mut bytes  = Vec::new();
+        let mut writer = WriteOptions::<f32>::new().default_dtype().shape(&[2, 3]).order(Order::Fortran).writer(&mut bytes).begin_nd().unwrap();
+        writer.extend(vec![1.0f32, 4.0, 2.0, 5.0, 3.0, 6.0]).unwrap();
+        writer.finish().unwrap();
+        let aperiodicity = Aperiodicity::read_npy(&bytes[..]).unwrap();
+        assert_eq!(aperiodicity.to_rows(), vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+    }
+
+    #[test]
+    fn test_npz_raw() {
+        let parameters = parameters();
+        let mut buffer = Cursor::new(Vec::new());
+        write_npz(&mut buffer, &parameters, None).unwrap();
+        buffer.set_position(0);
+        let read = read_npz(buffer).unwrap();
+        assert_eq!(read, parameters);
+        assert!(read.synthesize().is_ok());
+    }
+
+    #[test]
+    fn test_npz_signal_length() {
+        let parameters = parameters().with_signal_length(517);
+        let mut buffer = Cursor::new(Vec::new());
+        write_npz(&mut buffer, &parameters, Some(40)).unwrap();
+        buffer.set_position(0);
+        let mut npz = npyz::npz::NpzArchive::new(&mut buffer).unwrap();
+        for name in &["fs", "fft_size", "frame_period", "signal_length"] {
+            assert_eq!(npz.by_name(name).unwrap().unwrap().shape(), &[] as &[u64]);
+        }
+        assert_eq!(npz.by_name("frame_period").unwrap().unwrap().into_vec::<f64>().unwrap(), vec![5.0]);
+        buffer.set_position(0);
+        let read = read_npz(buffer).unwrap();
+        assert_eq!(read.signal_length(), 517);
+        assert_eq!(read.synthesize().unwrap().len(), 517);
+    }
+
+    #[test]
+    fn test_npz_coded() {
+        let parameters = parameters();
+        let mut buffer = Cursor::new(Vec::new());
+        write_npz(&mut buffer, &parameters, Some(40)).unwrap();
+        buffer.set_position(0);
+        let mut npz = npyz::npz::NpzArchive::new(&mut buffer).unwrap();
+        assert_eq!(npz.by_name("coded_sp").unwrap().unwrap().shape(), &[3, 40]);
+        assert!(npz.by_name("sp").unwrap().is_none());
+        buffer.set_position(0);
+        let read = read_npz(buffer).unwrap();
+        assert_eq!(read.fft_size(), 1024);
+        assert_eq!(read.f0(), parameters.f0());
+        let coded = code_aperiodicity(parameters.aperiodicity(), 16000).unwrap();
+        assert_eq!(read.aperiodicity(), &decode_aperiodicity(&coded, 16000, 1024).unwrap());
+    }
+}
@@ -0,0 +1,328 @@
+use std::f64::consts::LN_2;
+
+use crate::error::{WorldError, Result, check_fs, check_frame_period};
+
+fn check_positive(name: &'static str, value: f64) -> Result<()> {
+    if !(value.is_finite() && value > 0.0) {
+        return Err(WorldError::InvalidOption { name, value });
+    }
+    Ok(())
+}
+
+fn check_f0_range(fs: i32, f0_floor: f64, f0_ceil: f64) -> Result<()> {
+    check_fs(fs)?;
+    check_positive("f0_floor", f0_floor)?;
+    check_positive("f0_ceil",  f0_ceil)?;
+    if f0_ceil <= f0_floor || f0_ceil > fs as f64 / 2.0 {
+        return Err(WorldError::InvalidOption { name: "f0_ceil", value: f0_ceil });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DioOption {
+    pub f0_floor:           f64,
+    pub f0_ceil:            f64,
+    pub channels_in_octave: f64,
+    pub frame_period:       f64,
+    pub speed:              i32,
+    pub allowed_range:      f64,
+}
+
+impl Default for DioOption {
+    fn default() -> Self {
+        // WORLD's InitializeDioOption.
+        DioOption {
+            f0_floor:           71.0,
+            f0_ceil:            800.0,
+            channels_in_octave: 2.0,
+            frame_period:       5.0,
+            speed:              1,
+            allowed_range:      0.1,
+        }
+    }
+}
+
+impl DioOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn f0_floor(mut self, f0_floor: f64) -> Self {
+        self.f0_floor = f0_floor;
+        self
+    }
+
+    pub fn f0_ceil(mut self, f0_ceil: f64) -> Self {
+        self.f0_ceil = f0_ceil;
+        self
+    }
+
+    pub fn channels_in_octave(mut self, channels_in_octave: f64) -> Self {
+        self.channels_in_octave = channels_in_octave;
+        self
+    }
+
+    pub fn frame_period(mut self, frame_period: f64) -> Self {
+        self.frame_period = frame_period;
+        self
+    }
+
+    /// Decimation ratio, 1 (no decimation) to 12.
+    pub fn speed(mut self, speed: i32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn allowed_range(mut self, allowed_range: f64) -> Self {
+        self.allowed_range = allowed_range;
+        self
+    }
+
+    pub fn validate(&self, fs: i32) -> Result<()> {
+        check_f0_range(fs, self.f0_floor, self.f0_ceil)?;
+        check_frame_period(self.frame_period)?;
+        check_positive("channels_in_octave", self.channels_in_octave)?;
+        check_positive("allowed_range",      self.allowed_range)?;
+        if !(1..=12).contains(&self.speed) {
+            return Err(WorldError::InvalidOption { name: "speed", value: self.speed as f64 });
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ffi")]
+    pub(crate) fn to_sys(self) -> rsworld_sys::DioOption {
+        rsworld_sys::DioOption {
+            f0_floor:           self.f0_floor,
+            f0_ceil:            self.f0_ceil,
+            channels_in_octave: self.channels_in_octave,
+            frame_period:       self.frame_period,
+            speed:              self.speed,
+            allowed_range:      self.allowed_range,
+        }
+    }
+}
+
+#[cfg(feature = "ffi")]
+impl From<rsworld_sys::DioOption> for DioOption {
+    fn from(option: rsworld_sys::DioOption) -> Self {
+        DioOption {
+            f0_floor:           option.f0_floor,
+            f0_ceil:            option.f0_ceil,
+            channels_in_octave: option.channels_in_octave,
+            frame_period:       option.frame_period,
+            speed:              option.speed,
+            allowed_range:      option.allowed_range,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HarvestOption {
+    pub f0_floor:     f64,
+    pub f0_ceil:      f64,
+    pub frame_period: f64,
+}
+
+impl Default for HarvestOption {
+    fn default() -> Self {
+        // WORLD's InitializeHarvestOption.
+        HarvestOption { f0_floor: 71.0, f0_ceil: 800.0, frame_period: 5.0 }
+    }
+}
+
+impl HarvestOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn f0_floor(mut self, f0_floor: f64) -> Self {
+        self.f0_floor = f0_floor;
+        self
+    }
+
+    pub fn f0_ceil(mut self, f0_ceil: f64) -> Self {
+        self.f0_ceil = f0_ceil;
+        self
+    }
+
+    pub fn frame_period(mut self, frame_period: f64) -> Self {
+        self.frame_period = frame_period;
+        self
+    }
+
+    pub fn validate(&self, fs: i32) -> Result<()> {
+        check_f0_range(fs, self.f0_floor, self.f0_ceil)?;
+        check_frame_period(self.frame_period)
+    }
+
+    #[cfg(feature = "ffi")]
+    pub(crate) fn to_sys(self) -> rsworld_sys::HarvestOption {
+        rsworld_sys::HarvestOption {
+            f0_floor:     self.f0_floor,
+            f0_ceil:      self.f0_ceil,
+            frame_period: self.frame_period,
+        }
+    }
+}
+
+#[cfg(feature = "ffi")]
+impl From<rsworld_sys::HarvestOption> for HarvestOption {
+    fn from(option: rsworld_sys::HarvestOption) -> Self {
+        HarvestOption {
+            f0_floor:     option.f0_floor,
+            f0_ceil:      option.f0_ceil,
+            frame_period: option.frame_period,
+        }
+    }
+}
+
+/// CheapTrick settings. The fft size is derived from `f0_floor` and the sample rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheapTrickOption {
+    pub q1:       f64,
+    pub f0_floor: f64,
+}
+
+impl Default for CheapTrickOption {
+    fn default() -> Self {
+        // WORLD's InitializeCheapTrickOption, whose fft_size comes from `fft_size`.
+        CheapTrickOption { q1: -0.15, f0_floor: 71.0 }
+    }
+}
+
+impl CheapTrickOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn q1(mut self, q1: f64) -> Self {
+        self.q1 = q1;
+        self
+    }
+
+    pub fn f0_floor(mut self, f0_floor: f64) -> Self {
+        self.f0_floor = f0_floor;
+        self
+    }
+
+    pub fn validate(&self, fs: i32) -> Result<()> {
+        check_fs(fs)?;
+        check_positive("f0_floor", self.f0_floor)?;
+        if self.f0_floor >= fs as f64 / 2.0 {
+            return Err(WorldError::InvalidOption { name: "f0_floor", value: self.f0_floor });
+        }
+        if !(self.q1.is_finite() && (-0.5..=0.0).contains(&self.q1)) {
+            return Err(WorldError::InvalidOption { name: "q1", value: self.q1 });
+        }
+        Ok(())
+    }
+
+    pub fn fft_size(&self, fs: i32) -> Result<i32> {
+        self.validate(fs)?;
+        // GetFFTSizeForCheapTrick: the power of two above three periods of f0_floor.
+        Ok(2f64.powf(1.0 + ((3.0 * fs as f64 / self.f0_floor + 1.0).ln() / LN_2) as i32 as f64) as i32)
+    }
+
+    #[cfg(feature = "ffi")]
+    pub(crate) fn to_sys(self, fs: i32) -> Result<rsworld_sys::CheapTrickOption> {
+        let fft_size = self.fft_size(fs)?;
+        Ok(rsworld_sys::CheapTrickOption { q1: self.q1, f0_floor: self.f0_floor, fft_size })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct D4COption {
+    pub threshold: f64,
+}
+
+impl Default for D4COption {
+    fn default() -> Self {
+        // WORLD's InitializeD4COption.
+        D4COption { threshold: 0.85 }
+    }
+}
+
+impl D4COption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aperiodicity threshold above which a frame is treated as unvoiced, 0.0 to 1.0.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !(self.threshold.is_finite() && (0.0..=1.0).contains(&self.threshold)) {
+            return Err(WorldError::InvalidOption { name: "threshold", value: self.threshold });
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ffi")]
+    pub(crate) fn to_sys(self) -> rsworld_sys::D4COption {
+        rsworld_sys::D4COption { threshold: self.threshold }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DioOption, HarvestOption, CheapTrickOption, D4COption, WorldError};
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(DioOption::default(), DioOption { f0_floor: 71.0, f0_ceil: 800.0, channels_in_octave: 2.0, frame_period: 5.0, speed: 1, allowed_range: 0.1 });
+        assert_eq!(HarvestOption::default(), HarvestOption { f0_floor: 71.0, f0_ceil: 800.0, frame_period: 5.0 });
+        assert_eq!(CheapTrickOption::default(), CheapTrickOption { q1: -0.15, f0_floor: 71.0 });
+        assert_eq!(D4COption::default(), D4COption { threshold: 0.85 });
+    }
+
+    #[test]
+    fn test_builders() {
+        let option = DioOption::new().f0_floor(50.0).f0_ceil(600.0).speed(3).allowed_range(0.2).frame_period(10.0);
+        assert_eq!(option.f0_floor,      50.0);
+        assert_eq!(option.f0_ceil,       600.0);
+        assert_eq!(option.speed,         3);
+        assert_eq!(option.allowed_range, 0.2);
+        assert_eq!(option.frame_period,  10.0);
+        assert_eq!(option.validate(16000), Ok(()));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(DioOption::new().speed(13).validate(16000),
+                   Err(WorldError::InvalidOption { name: "speed", value: 13.0 }));
+        assert_eq!(HarvestOption::new().f0_ceil(60.0).validate(16000),
+                   Err(WorldError::InvalidOption { name: "f0_ceil", value: 60.0 }));
+        assert_eq!(HarvestOption::new().f0_ceil(5000.0).validate(8000),
+                   Err(WorldError::InvalidOption { name: "f0_ceil", value: 5000.0 }));
+        assert_eq!(CheapTrickOption::new().q1(1.0).validate(16000),
+                   Err(WorldError::InvalidOption { name: "q1", value: 1.0 }));
+        assert_eq!(D4COption::new().threshold(-0.1).validate(),
+                   Err(WorldError::InvalidOption { name: "threshold", value: -0.1 }));
+    }
+
+    #[test]
+    fn test_cheaptrick_fft_size() {
+        assert_eq!(CheapTrickOption::new().fft_size(44100), Ok(2048));
+        assert_eq!(CheapTrickOption::new().f0_floor(40.0).fft_size(44100), Ok(4096));
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_matches_world() {
+        assert_eq!(DioOption::default(),        DioOption::from(rsworld_sys::DioOption::new()));
+        assert_eq!(HarvestOption::default(),    HarvestOption::from(rsworld_sys::HarvestOption::new()));
+        assert_eq!(D4COption::default(),        D4COption { threshold: rsworld_sys::D4COption::new().threshold });
+        for &fs in &[8000, 16000, 22050, 44100, 48000, 96000] {
+            let world  = rsworld_sys::CheapTrickOption::new(fs);
+            let option = CheapTrickOption::default();
+            assert_eq!(option, CheapTrickOption { q1: world.q1, f0_floor: world.f0_floor });
+            for &f0_floor in &[40.0, 71.0, 100.0, 150.0] {
+                assert_eq!(option.f0_floor(f0_floor).fft_size(fs), crate::get_fft_size_for_cheaptrick(fs, &option.f0_floor(f0_floor)));
+            }
+        }
+    }
+}
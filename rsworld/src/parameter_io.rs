@@ -0,0 +1,324 @@
+use std::io::{Read, Write};
+
+use crate::{Spectrogram, Aperiodicity, CodedSpectrogram, CodedAperiodicity, decode_spectral_envelope, decode_aperiodicity};
+use crate::error::{WorldError, Result, check_fs, check_frame_period, check_fft_size, check_length};
+
+// Layout of WORLD's tools/parameterio.cpp, little-endian:
+//   "F0  " "NOF " i32 "FP  " f64 data
+//   "SPEC" | "AP  " "NOF " i32 "FP  " f64 "FFT " i32 "NOD " i32 "FS  " i32 data
+// NOD is 0 for a raw fft_size/2+1 bin envelope and the coded dimension otherwise.
+const F0:   &[u8; 4] = b"F0  ";
+const SPEC: &[u8; 4] = b"SPEC";
+const AP:   &[u8; 4] = b"AP  ";
+
+/// Header of a spectral envelope or aperiodicity file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParameterHeader {
+    pub frames:       usize,
+    pub frame_period: f64,
+    pub fft_size:     i32,
+    /// 0 for a raw envelope, otherwise the number of coded dimensions.
+    pub dimensions:   i32,
+    pub fs:           i32,
+}
+
+impl ParameterHeader {
+    pub fn is_coded(&self) -> bool {
+        self.dimensions != 0
+    }
+
+    /// Values stored per frame.
+    pub fn columns(&self) -> usize {
+        if self.is_coded() {
+            self.dimensions as usize
+        } else {
+            self.fft_size as usize / 2 + 1
+        }
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, magic: &[u8; 4]) -> Result<()> {
+        writer.write_all(magic)?;
+        write_i32(writer, b"NOF ", check_length("frames", self.frames)?)?;
+        writer.write_all(b"FP  ")?;
+        writer.write_all(&self.frame_period.to_le_bytes())?;
+        write_i32(writer, b"FFT ", self.fft_size)?;
+        write_i32(writer, b"NOD ", self.dimensions)?;
+        write_i32(writer, b"FS  ", self.fs)
+    }
+
+    fn read<R: Read>(reader: &mut R, magic: &[u8; 4]) -> Result<Self> {
+        read_tag(reader, magic)?;
+        let frames       = read_frames(reader)?;
+        let frame_period = read_f64(reader, b"FP  ")?;
+        let fft_size     = read_i32(reader, b"FFT ")?;
+        let dimensions   = read_i32(reader, b"NOD ")?;
+        let fs           = read_i32(reader, b"FS  ")?;
+        check_frame_period(frame_period)?;
+        check_fft_size(fft_size)?;
+        check_fs(fs)?;
+        if dimensions < 0 {
+            return Err(WorldError::InvalidDimensions(dimensions));
+        }
+        Ok(ParameterHeader { frames, frame_period, fft_size, dimensions, fs })
+    }
+}
+
+fn write_i32<W: Write>(writer: &mut W, tag: &[u8; 4], value: i32) -> Result<()> {
+    writer.write_all(tag)?;
+    writer.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+fn write_values<W: Write>(writer: &mut W, values: &[f64]) -> Result<()> {
+    let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+fn read_tag<R: Read>(reader: &mut R, tag: &[u8; 4]) -> Result<()> {
+    let mut found = [0u8; 4];
+    reader.read_exact(&mut found)?;
+    if &found != tag {
+        return Err(WorldError::InvalidFormat(format!("expected {:?}, found {:?}",
+                                                     String::from_utf8_lossy(tag), String::from_utf8_lossy(&found))));
+    }
+    Ok(())
+}
+
+fn read_i32<R: Read>(reader: &mut R, tag: &[u8; 4]) -> Result<i32> {
+    read_tag(reader, tag)?;
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(i32::from_le_bytes(bytes))
+}
+
+fn read_f64<R: Read>(reader: &mut R, tag: &[u8; 4]) -> Result<f64> {
+    read_tag(reader, tag)?;
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+fn read_frames<R: Read>(reader: &mut R) -> Result<usize> {
+    let frames = read_i32(reader, b"NOF ")?;
+    if frames < 0 {
+        return Err(WorldError::InvalidFormat(format!("negative number of frames {}", frames)));
+    }
+    Ok(frames as usize)
+}
+
+fn read_values<R: Read>(reader: &mut R, frames: usize, columns: usize) -> Result<Vec<f64>> {
+    let length = frames.checked_mul(columns).and_then(|length| length.checked_mul(8)).ok_or_else(|| {
+        WorldError::InvalidFormat(format!("{} frames of {} values do not fit in memory", frames, columns))
+    })?;
+    // The header alone does not decide the allocation, so a corrupt length runs
+    // into the end of the input instead.
+    let mut bytes = Vec::new();
+    reader.take(length as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < length {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(bytes.chunks_exact(8).map(|v| f64::from_le_bytes([v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]])).collect())
+}
+
+/// Writes the binary form of WORLD's `WriteF0`.
+pub fn write_f0<W: Write>(mut writer: W, f0: &[f64], frame_period: f64) -> Result<()> {
+    check_frame_period(frame_period)?;
+    writer.write_all(F0)?;
+    write_i32(&mut writer, b"NOF ", check_length("f0", f0.len())?)?;
+    writer.write_all(b"FP  ")?;
+    writer.write_all(&frame_period.to_le_bytes())?;
+    write_values(&mut writer, f0)
+}
+
+/// Reads a file written by WORLD's `WriteF0`. Returns `(temporal_positions, f0, frame_period)`.
+pub fn read_f0<R: Read>(mut reader: R) -> Result<(Vec<f64>, Vec<f64>, f64)> {
+    read_tag(&mut reader, F0)?;
+    let frames       = read_frames(&mut reader)?;
+    let frame_period = read_f64(&mut reader, b"FP  ")?;
+    check_frame_period(frame_period)?;
+    let f0 = read_values(&mut reader, frames, 1)?;
+    // Same expression as ReadF0, so the positions match the C reader bit for bit.
+    let temporal_positions = (0..frames).map(|i| i as f64 / 1000.0 * frame_period).collect();
+    Ok((temporal_positions, f0, frame_period))
+}
+
+pub fn write_spectral_envelope<W: Write>(mut writer: W, spectrogram: &Spectrogram, fs: i32, frame_period: f64) -> Result<()> {
+    let fft_size = check_length("spectrogram", spectrogram.fft_size())?;
+    let header   = ParameterHeader { frames: spectrogram.frames(), frame_period, fft_size, dimensions: 0, fs };
+    write_matrix(&mut writer, SPEC, header, spectrogram.as_slice())
+}
+
+pub fn write_coded_spectral_envelope<W: Write>(mut writer: W, coded_spectrogram: &CodedSpectrogram, fs: i32, frame_period: f64, fft_size: i32) -> Result<()> {
+    let dimensions = check_length("coded_spectrogram", coded_spectrogram.dimensions())?;
+    let header     = ParameterHeader { frames: coded_spectrogram.frames(), frame_period, fft_size, dimensions, fs };
+    write_matrix(&mut writer, SPEC, header, coded_spectrogram.as_slice())
+}
+
+pub fn write_aperiodicity<W: Write>(mut writer: W, aperiodicity: &Aperiodicity, fs: i32, frame_period: f64) -> Result<()> {
+    let fft_size = check_length("aperiodicity", aperiodicity.fft_size())?;
+    let header   = ParameterHeader { frames: aperiodicity.frames(), frame_period, fft_size, dimensions: 0, fs };
+    write_matrix(&mut writer, AP, header, aperiodicity.as_slice())
+}
+
+pub fn write_coded_aperiodicity<W: Write>(mut writer: W, coded_aperiodicity: &CodedAperiodicity, fs: i32, frame_period: f64, fft_size: i32) -> Result<()> {
+    let dimensions = check_length("coded_aperiodicity", coded_aperiodicity.dimensions())?;
+    let header     = ParameterHeader { frames: coded_aperiodicity.frames(), frame_period, fft_size, dimensions, fs };
+    write_matrix(&mut writer, AP, header, coded_aperiodicity.as_slice())
+}
+
+fn write_matrix<W: Write>(writer: &mut W, magic: &[u8; 4], header: ParameterHeader, data: &[f64]) -> Result<()> {
+    check_fs(header.fs)?;
+    check_frame_period(header.frame_period)?;
+    check_fft_size(header.fft_size)?;
+    header.write(writer, magic)?;
+    write_values(writer, data)
+}
+
+/// Reads a spectral envelope file, decoding it first if it holds a coded envelope.
+pub fn read_spectral_envelope<R: Read>(mut reader: R) -> Result<(ParameterHeader, Spectrogram)> {
+    let header = ParameterHeader::read(&mut reader, SPEC)?;
+    let data   = read_values(&mut reader, header.frames, header.columns())?;
+    if header.is_coded() {
+        let coded_spectrogram = CodedSpectrogram::from_vec(header.frames, header.columns(), data)?;
+        return Ok((header, decode_spectral_envelope(&coded_spectrogram, header.fs, header.fft_size)?));
+    }
+    Ok((header, Spectrogram::from_vec(header.frames, header.columns(), data)?))
+}
+
+pub fn read_coded_spectral_envelope<R: Read>(mut reader: R) -> Result<(ParameterHeader, CodedSpectrogram)> {
+    let header = ParameterHeader::read(&mut reader, SPEC)?;
+    if !header.is_coded() {
+        return Err(WorldError::InvalidFormat("spectral envelope is not coded".to_string()));
+    }
+    let data = read_values(&mut reader, header.frames, header.columns())?;
+    Ok((header, CodedSpectrogram::from_vec(header.frames, header.columns(), data)?))
+}
+
+/// Reads an aperiodicity file, decoding it first if it holds a coded aperiodicity.
+pub fn read_aperiodicity<R: Read>(mut reader: R) -> Result<(ParameterHeader, Aperiodicity)> {
+    let header = ParameterHeader::read(&mut reader, AP)?;
+    let data   = read_values(&mut reader, header.frames, header.columns())?;
+    if header.is_coded() {
+        let coded_aperiodicity = CodedAperiodicity::from_vec(header.frames, header.columns(), data)?;
+        return Ok((header, decode_aperiodicity(&coded_aperiodicity, header.fs, header.fft_size)?));
+    }
+    Ok((header, Aperiodicity::from_vec(header.frames, header.columns(), data)?))
+}
+
+pub fn read_coded_aperiodicity<R: Read>(mut reader: R) -> Result<(ParameterHeader, CodedAperiodicity)> {
+    let header = ParameterHeader::read(&mut reader, AP)?;
+    if !header.is_coded() {
+        return Err(WorldError::InvalidFormat("aperiodicity is not coded".to_string()));
+    }
+    let data = read_values(&mut reader, header.frames, header.columns())?;
+    Ok((header, CodedAperiodicity::from_vec(header.frames, header.columns(), data)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Spectrogram,
+        Aperiodicity,
+        ParameterHeader,
+        WorldError,
+        code_aperiodicity,
+        decode_aperiodicity,
+        read_f0,
+        write_f0,
+        read_spectral_envelope,
+        read_coded_spectral_envelope,
+        write_spectral_envelope,
+        read_aperiodicity,
+        write_aperiodicity,
+        read_coded_aperiodicity,
+        write_coded_aperiodicity,
+    };
+
+    #[test]
+    fn test_f0_bytes() {
+        let mut bytes = Vec::new();
+        write_f0(&mut bytes, &[100.0, 0.0], 5.0).unwrap();
+        let mut expected = b"F0  NOF ".to_vec();
+        expected.extend_from_slice(&2i32.to_le_bytes());
+        expected.extend_from_slice(b"FP  ");
+        expected.extend_from_slice(&5.0f64.to_le_bytes());
+        expected.extend_from_slice(&100.0f64.to_le_bytes());
+        expected.extend_from_slice(&0.0f64.to_le_bytes());
+        assert_eq!(bytes, expected);
+        assert_eq!(read_f0(&bytes[..]), Ok((vec![0.0, 0.005], vec![100.0, 0.0], 5.0)));
+    }
+
+    #[test]
+    fn test_spectral_envelope_bytes() {
+        let spectrogram = Spectrogram::from_vec(1, 3, vec![1.0, 2.0, 3.0]).unwrap();
+        let mut bytes   = Vec::new();
+        write_spectral_envelope(&mut bytes, &spectrogram, 16000, 5.0).unwrap();
+        let mut expected = b"SPECNOF ".to_vec();
+        expected.extend_from_slice(&1i32.to_le_bytes());
+        expected.extend_from_slice(b"FP  ");
+        expected.extend_from_slice(&5.0f64.to_le_bytes());
+        expected.extend_from_slice(b"FFT ");
+        expected.extend_from_slice(&4i32.to_le_bytes());
+        expected.extend_from_slice(b"NOD ");
+        expected.extend_from_slice(&0i32.to_le_bytes());
+        expected.extend_from_slice(b"FS  ");
+        expected.extend_from_slice(&16000i32.to_le_bytes());
+        for v in &[1.0f64, 2.0, 3.0] {
+            expected.extend_from_slice(&v.to_le_bytes());
+        }
+        assert_eq!(bytes, expected);
+        let (header, read) = read_spectral_envelope(&bytes[..]).unwrap();
+        assert_eq!(header, ParameterHeader { frames: 1, frame_period: 5.0, fft_size: 4, dimensions: 0, fs: 16000 });
+        assert_eq!(read, spectrogram);
+    }
+
+    #[test]
+    fn test_coded_round_trip() {
+        let fs               = 16000;
+        let mut aperiodicity = Aperiodicity::with_fft_size(4, 1024);
+        aperiodicity.as_mut_slice().iter_mut().for_each(|v| *v = 0.5);
+        let coded            = code_aperiodicity(&aperiodicity, fs).unwrap();
+        let mut bytes        = Vec::new();
+        write_coded_aperiodicity(&mut bytes, &coded, fs, 5.0, 1024).unwrap();
+        let (header, read) = read_coded_aperiodicity(&bytes[..]).unwrap();
+        assert!(header.is_coded());
+        assert_eq!(read, coded);
+        let (_, decoded) = read_aperiodicity(&bytes[..]).unwrap();
+        assert_eq!(decoded, decode_aperiodicity(&coded, fs, 1024).unwrap());
+        assert!(matches!(read_aperiodicity(&bytes[..10]), Err(WorldError::Io(_))));
+    }
+
+    #[test]
+    fn test_wrong_header() {
+        let mut bytes = Vec::new();
+        write_aperiodicity(&mut bytes, &Aperiodicity::with_fft_size(1, 4), 16000, 5.0).unwrap();
+        assert_eq!(read_spectral_envelope(&bytes[..]),
+                   Err(WorldError::InvalidFormat("expected \"SPEC\", found \"AP  \"".to_string())));
+        assert_eq!(read_coded_aperiodicity(&bytes[..]),
+                   Err(WorldError::InvalidFormat("aperiodicity is not coded".to_string())));
+    }
+
+    #[test]
+    fn test_corrupt_length() {
+        let header = |frames: i32, dimensions: i32| {
+            let mut bytes = b"SPECNOF ".to_vec();
+            bytes.extend_from_slice(&frames.to_le_bytes());
+            bytes.extend_from_slice(b"FP  ");
+            bytes.extend_from_slice(&5.0f64.to_le_bytes());
+            bytes.extend_from_slice(b"FFT ");
+            bytes.extend_from_slice(&1024i32.to_le_bytes());
+            bytes.extend_from_slice(b"NOD ");
+            bytes.extend_from_slice(&dimensions.to_le_bytes());
+            bytes.extend_from_slice(b"FS  ");
+            bytes.extend_from_slice(&16000i32.to_le_bytes());
+            bytes.extend_from_slice(&1.0f64.to_le_bytes());
+            bytes
+        };
+        // 2 GiB of data announced, one value present.
+        assert!(matches!(read_coded_spectral_envelope(&header(1 << 24, 16)[..]), Err(WorldError::Io(_))));
+        if cfg!(target_pointer_width = "64") {
+            assert!(matches!(read_coded_spectral_envelope(&header(i32::MAX, i32::MAX)[..]), Err(WorldError::InvalidFormat(_))));
+        }
+    }
+}
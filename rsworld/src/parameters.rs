@@ -0,0 +1,113 @@
+use crate::{Spectrogram, Aperiodicity, synthesis_with_length, get_samples_for_synthesis};
+use crate::error::{Result, check_fs, check_frame_period, check_same_length};
+
+/// The result of one analysis pass, together with the settings it was made with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldParameters {
+    f0:                 Vec<f64>,
+    temporal_positions: Vec<f64>,
+    spectrogram:        Spectrogram,
+    aperiodicity:       Aperiodicity,
+    fs:                 i32,
+    frame_period:       f64,
+    signal_length:      usize,
+}
+
+impl WorldParameters {
+    pub fn new(f0: Vec<f64>, temporal_positions: Vec<f64>, spectrogram: Spectrogram, aperiodicity: Aperiodicity, fs: i32, frame_period: f64) -> Result<Self> {
+        check_fs(fs)?;
+        check_frame_period(frame_period)?;
+        check_same_length("temporal_positions", f0.len(),           temporal_positions.len())?;
+        check_same_length("spectrogram",        f0.len(),           spectrogram.frames())?;
+        check_same_length("aperiodicity",       f0.len(),           aperiodicity.frames())?;
+        check_same_length("aperiodicity bins",  spectrogram.bins(), aperiodicity.bins())?;
+        let signal_length = get_samples_for_synthesis(f0.len(), frame_period, fs);
+        Ok(WorldParameters { f0, temporal_positions, spectrogram, aperiodicity, fs, frame_period, signal_length })
+    }
+
+    /// Sets the number of samples `synthesize` produces, normally the length of the analysed input.
+    pub fn with_signal_length(mut self, signal_length: usize) -> Self {
+        self.signal_length = signal_length;
+        self
+    }
+
+    pub fn f0(&self) -> &[f64] {
+        &self.f0
+    }
+
+    pub fn f0_mut(&mut self) -> &mut [f64] {
+        &mut self.f0
+    }
+
+    pub fn temporal_positions(&self) -> &[f64] {
+        &self.temporal_positions
+    }
+
+    pub fn spectrogram(&self) -> &Spectrogram {
+        &self.spectrogram
+    }
+
+    pub fn spectrogram_mut(&mut self) -> &mut Spectrogram {
+        &mut self.spectrogram
+    }
+
+    pub fn aperiodicity(&self) -> &Aperiodicity {
+        &self.aperiodicity
+    }
+
+    pub fn aperiodicity_mut(&mut self) -> &mut Aperiodicity {
+        &mut self.aperiodicity
+    }
+
+    pub fn fs(&self) -> i32 {
+        self.fs
+    }
+
+    pub fn frame_period(&self) -> f64 {
+        self.frame_period
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.spectrogram.fft_size()
+    }
+
+    pub fn signal_length(&self) -> usize {
+        self.signal_length
+    }
+
+    pub fn frames(&self) -> usize {
+        self.f0.len()
+    }
+
+    pub fn into_parts(self) -> (Vec<f64>, Vec<f64>, Spectrogram, Aperiodicity) {
+        (self.f0, self.temporal_positions, self.spectrogram, self.aperiodicity)
+    }
+
+    pub fn synthesize(&self) -> Result<Vec<f64>> {
+        synthesis_with_length(&self.f0, &self.spectrogram, &self.aperiodicity, self.frame_period, self.fs, self.signal_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{WorldParameters, Spectrogram, Aperiodicity, WorldError};
+
+    #[test]
+    fn test_new() {
+        let spectrogram  = Spectrogram::with_fft_size(2, 1024);
+        let aperiodicity = Aperiodicity::with_fft_size(2, 1024);
+        let parameters   = WorldParameters::new(vec![0.0; 2], vec![0.0, 0.005], spectrogram, aperiodicity, 16000, 5.0).unwrap();
+        assert_eq!(parameters.frames(),   2);
+        assert_eq!(parameters.fft_size(), 1024);
+        assert_eq!(parameters.signal_length(), 160);
+        assert_eq!(parameters.with_signal_length(150).signal_length(), 150);
+    }
+
+    #[test]
+    fn test_new_mismatch() {
+        let spectrogram  = Spectrogram::with_fft_size(2, 1024);
+        let aperiodicity = Aperiodicity::with_fft_size(3, 1024);
+        assert_eq!(WorldParameters::new(vec![0.0; 2], vec![0.0, 0.005], spectrogram, aperiodicity, 16000, 5.0),
+                   Err(WorldError::LengthMismatch { name: "aperiodicity", expected: 2, actual: 3 }));
+    }
+}
@@ -0,0 +1,231 @@
+use rsworld_sys::{
+    WorldSynthesizer,
+    InitializeSynthesizer,
+    AddParameters,
+    RefreshSynthesizer,
+    DestroySynthesizer,
+    IsLocked,
+    Synthesis2,
+};
+
+use crate::{Spectrogram, Aperiodicity};
+use crate::error::{
+    WorldError,
+    Result,
+    check_fs,
+    check_frame_period,
+    check_fft_size,
+    check_length,
+    check_same_length,
+    check_finite,
+};
+
+// Parameters handed to AddParameters. The synthesizer keeps the row pointers
+// until the ring buffer slot is reused, so they live here until then.
+struct Slot {
+    _spectrogram:     Spectrogram,
+    _aperiodicity:    Aperiodicity,
+    _spectrogram_ptr:  Vec<*mut f64>,
+    _aperiodicity_ptr: Vec<*mut f64>,
+}
+
+/// Safe wrapper around WORLD's ring-buffer synthesizer (`synthesisrealtime.cpp`).
+pub struct RealtimeSynthesizer {
+    synth: Box<WorldSynthesizer>,
+    slots: Vec<Option<Slot>>,
+}
+
+impl RealtimeSynthesizer {
+    pub fn new(fs: i32, frame_period: f64, fft_size: i32, buffer_size: usize, number_of_pointers: usize) -> Result<Self> {
+        check_fs(fs)?;
+        check_frame_period(frame_period)?;
+        check_fft_size(fft_size)?;
+        if buffer_size == 0 {
+            return Err(WorldError::InvalidOption { name: "buffer_size", value: 0.0 });
+        }
+        if number_of_pointers == 0 {
+            return Err(WorldError::InvalidOption { name: "number_of_pointers", value: 0.0 });
+        }
+        let buffer_size        = check_length("buffer_size",        buffer_size)?;
+        let number_of_pointers = check_length("number_of_pointers", number_of_pointers)?;
+        // Every field is an integer, a double or a pointer, so all-zero is a valid value.
+        let mut synth: Box<WorldSynthesizer> = Box::new(unsafe { std::mem::MaybeUninit::zeroed().assume_init() });
+        unsafe {
+            InitializeSynthesizer(fs, frame_period, fft_size, buffer_size, number_of_pointers, synth.as_mut() as *mut _);
+        }
+        let slots = (0..number_of_pointers).map(|_| None).collect();
+        Ok(RealtimeSynthesizer { synth, slots })
+    }
+
+    pub fn fs(&self) -> i32 {
+        self.synth.fs
+    }
+
+    pub fn fft_size(&self) -> i32 {
+        self.synth.fft_size
+    }
+
+    pub fn buffer_size(&self) -> usize {
+        self.synth.buffer_size as usize
+    }
+
+    /// Queues frames for synthesis. Returns `false` when the ring buffer is full.
+    pub fn add_parameters(&mut self, f0: &[f64], spectrogram: &Spectrogram, aperiodicity: &Aperiodicity) -> Result<bool> {
+        if f0.is_empty() {
+            return Err(WorldError::TooFewFrames { minimum: 1, actual: 0 });
+        }
+        check_finite("f0", f0)?;
+        let f0_length = check_length("f0", f0.len())?;
+        let bins      = (self.synth.fft_size/2+1) as usize;
+        check_same_length("spectrogram",       f0.len(), spectrogram.frames())?;
+        check_same_length("aperiodicity",      f0.len(), aperiodicity.frames())?;
+        check_same_length("spectrogram bins",  bins,     spectrogram.bins())?;
+        check_same_length("aperiodicity bins", bins,     aperiodicity.bins())?;
+
+        let mut f0               = f0.to_vec();
+        let mut spectrogram      = spectrogram.clone();
+        let mut aperiodicity     = aperiodicity.clone();
+        let mut spectrogram_ptr  = spectrogram.row_ptrs_mut();
+        let mut aperiodicity_ptr = aperiodicity.row_ptrs_mut();
+        let pointer = (self.synth.head_pointer % self.synth.number_of_pointers) as usize;
+        let added;
+        unsafe {
+            added = AddParameters(f0.as_mut_ptr(), f0_length, spectrogram_ptr.as_mut_ptr(), aperiodicity_ptr.as_mut_ptr(), self.synth.as_mut() as *mut _);
+        }
+        if added == 0 {
+            return Ok(false);
+        }
+        self.slots[pointer] = Some(Slot {
+            _spectrogram:      spectrogram,
+            _aperiodicity:     aperiodicity,
+            _spectrogram_ptr:  spectrogram_ptr,
+            _aperiodicity_ptr: aperiodicity_ptr,
+        });
+        Ok(true)
+    }
+
+    /// The ring buffer is full and cannot produce more output; call `refresh`.
+    pub fn is_locked(&self) -> bool {
+        // IsLocked only reads the synthesizer.
+        unsafe {
+            IsLocked(self.synth.as_ref() as *const _ as *mut _) != 0
+        }
+    }
+
+    /// Renders the next `buffer_size` samples, or `None` if not enough frames are queued.
+    pub fn synthesize(&mut self) -> Option<&[f64]> {
+        let synthesized;
+        unsafe {
+            synthesized = Synthesis2(self.synth.as_mut() as *mut _);
+        }
+        if synthesized == 0 {
+            return None;
+        }
+        unsafe {
+            Some(std::slice::from_raw_parts(self.synth.buffer, self.synth.buffer_size as usize))
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        // Stale slots may still be read until they are overwritten, so they are kept.
+        unsafe {
+            RefreshSynthesizer(self.synth.as_mut() as *mut _);
+        }
+    }
+}
+
+impl Drop for RealtimeSynthesizer {
+    fn drop(&mut self) {
+        unsafe {
+            DestroySynthesizer(self.synth.as_mut() as *mut _);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RealtimeSynthesizer, Spectrogram, Aperiodicity, WorldError, synthesis};
+
+    fn parameters(frames: usize, fft_size: usize) -> (Vec<f64>, Spectrogram, Aperiodicity) {
+        let f0               = vec![150.0; frames];
+        let mut spectrogram  = Spectrogram::with_fft_size(frames, fft_size);
+        let mut aperiodicity = Aperiodicity::with_fft_size(frames, fft_size);
+        spectrogram.as_mut_slice().iter_mut().for_each(|v| *v = 1e-4);
+        aperiodicity.as_mut_slice().iter_mut().for_each(|v| *v = 0.1);
+        (f0, spectrogram, aperiodicity)
+    }
+
+    #[test]
+    fn test_realtime_synthesis() {
+        let (f0, spectrogram, aperiodicity) = parameters(100, 1024);
+        let mut synthesizer = RealtimeSynthesizer::new(16000, 5.0, 1024, 256, 16).unwrap();
+        assert_eq!(synthesizer.add_parameters(&f0, &spectrogram, &aperiodicity), Ok(true));
+        let mut y = Vec::new();
+        while let Some(buffer) = synthesizer.synthesize() {
+            assert_eq!(buffer.len(), 256);
+            y.extend_from_slice(buffer);
+        }
+        assert!(y.len() >= 256);
+        assert!(y.iter().any(|v| *v != 0.0));
+        assert!(y.iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_realtime_frame_by_frame() {
+        let (f0, spectrogram, mut aperiodicity) = parameters(40, 1024);
+        // Without noise the output is deterministic.
+        aperiodicity.as_mut_slice().iter_mut().for_each(|v| *v = 0.0);
+        let mut synthesizer = RealtimeSynthesizer::new(16000, 5.0, 1024, 80, 64).unwrap();
+        let mut y = Vec::new();
+        for frame in 0..f0.len() {
+            let spectrogram_row  = Spectrogram::from_vec(1, 513, spectrogram.row(frame).to_vec()).unwrap();
+            let aperiodicity_row = Aperiodicity::from_vec(1, 513, aperiodicity.row(frame).to_vec()).unwrap();
+            assert_eq!(synthesizer.add_parameters(&f0[frame..frame+1], &spectrogram_row, &aperiodicity_row), Ok(true));
+            while let Some(buffer) = synthesizer.synthesize() {
+                y.extend_from_slice(buffer);
+            }
+        }
+        let reference = synthesis(&f0, &spectrogram, &aperiodicity, 5.0, 16000).unwrap();
+        assert!(!y.is_empty());
+        assert!(y.len() <= reference.len());
+
+        // The realtime synthesizer places pulses on whole samples while
+        // `synthesis` shifts them by the fractional remainder, so the pulses
+        // are compared by position and energy rather than sample by sample.
+        let peak   = reference.iter().fold(0.0f64, |peak, v| peak.max(v.abs()));
+        let pulses = |y: &[f64]| -> Vec<usize> {
+            (1..y.len()-1).filter(|&i| y[i].abs() > 0.5 * peak && y[i].abs() >= y[i-1].abs() && y[i].abs() >= y[i+1].abs()).collect()
+        };
+        let energy = |y: &[f64], pulse: usize| -> f64 {
+            y[pulse.saturating_sub(40)..(pulse+40).min(y.len())].iter().map(|v| v * v).sum()
+        };
+        let streamed = pulses(&y);
+        let expected = pulses(&reference[..y.len()]);
+        assert_eq!(streamed.len(), expected.len());
+        assert!(streamed.len() > 20);
+        for (&streamed, &expected) in streamed.iter().zip(&expected) {
+            assert!(streamed.abs_diff(expected) <= 1, "pulse at {} != {}", streamed, expected);
+            let ratio = energy(&y, streamed) / energy(&reference, expected);
+            assert!((ratio - 1.0).abs() < 0.15, "pulse at {}: energy ratio {}", streamed, ratio);
+        }
+    }
+
+    #[test]
+    fn test_realtime_refresh() {
+        let (f0, spectrogram, aperiodicity) = parameters(10, 1024);
+        let mut synthesizer = RealtimeSynthesizer::new(16000, 5.0, 1024, 64, 2).unwrap();
+        assert_eq!(synthesizer.add_parameters(&f0, &spectrogram, &aperiodicity), Ok(true));
+        assert_eq!(synthesizer.add_parameters(&f0, &spectrogram, &aperiodicity), Ok(true));
+        assert_eq!(synthesizer.add_parameters(&f0, &spectrogram, &aperiodicity), Ok(false));
+        synthesizer.refresh();
+        assert_eq!(synthesizer.add_parameters(&f0, &spectrogram, &aperiodicity), Ok(true));
+    }
+
+    #[test]
+    fn test_realtime_bins_mismatch() {
+        let (f0, spectrogram, aperiodicity) = parameters(10, 512);
+        let mut synthesizer = RealtimeSynthesizer::new(16000, 5.0, 1024, 64, 2).unwrap();
+        assert_eq!(synthesizer.add_parameters(&f0, &spectrogram, &aperiodicity),
+                   Err(WorldError::LengthMismatch { name: "spectrogram bins", expected: 513, actual: 257 }));
+    }
+}
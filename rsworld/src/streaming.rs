@@ -0,0 +1,300 @@
+use crate::{Analyzer, WorldParameters, Spectrogram, Aperiodicity, cheaptrick, d4c};
+use crate::error::{WorldError, Result, check_finite};
+
+/// Analyses a signal that arrives in chunks.
+///
+/// Frames are emitted at `k * frame_period` from the start of the stream, exactly
+/// like a whole-file run. Each batch of frames is analysed on a window that
+/// extends `context_frames` beyond it on both sides, so only that much audio is held.
+/// Windows start on whole samples, so frame periods that take more than 1000
+/// frames to span one are rejected.
+pub struct StreamingAnalyzer {
+    analyzer:       Analyzer,
+    fs:             i32,
+    fft_size:       i32,
+    chunk_frames:   usize,
+    context_frames: usize,
+    // Smallest number of frames that spans a whole number of samples.
+    alignment:      usize,
+    buffer:         Vec<f64>,
+    buffer_origin:  usize,
+    received:       usize,
+    next_frame:     usize,
+}
+
+impl StreamingAnalyzer {
+    pub fn new(analyzer: Analyzer, fs: i32) -> Result<Self> {
+        analyzer.validate(fs)?;
+        let fft_size       = analyzer.cheaptrick_option().fft_size(fs)?;
+        let frame_samples  = analyzer.period() * fs as f64 / 1000.0;
+        // Enough for the CheapTrick/D4C windows and the F0 estimators' filters.
+        let context        = (fft_size as f64).max(0.1 * fs as f64);
+        let context_frames = (context / frame_samples).ceil() as usize;
+        let alignment      = (1..=1000).find(|m| {
+            let samples = *m as f64 * frame_samples;
+            (samples - samples.round()).abs() < 1e-6
+        }).ok_or(WorldError::InvalidOption { name: "frame_period", value: analyzer.period() })?;
+        Ok(StreamingAnalyzer {
+            analyzer,
+            fs,
+            fft_size,
+            chunk_frames:  200,
+            context_frames,
+            alignment,
+            buffer:        Vec::new(),
+            buffer_origin: 0,
+            received:      0,
+            next_frame:    0,
+        })
+    }
+
+    /// Number of frames analysed per window.
+    pub fn chunk_frames(mut self, chunk_frames: usize) -> Self {
+        self.chunk_frames = chunk_frames.max(1);
+        self
+    }
+
+    /// Number of frames of audio kept on each side of a window.
+    pub fn context_frames(mut self, context_frames: usize) -> Result<Self> {
+        if context_frames == 0 {
+            return Err(WorldError::InvalidOption { name: "context_frames", value: 0.0 });
+        }
+        self.context_frames = context_frames;
+        Ok(self)
+    }
+
+    pub fn fs(&self) -> i32 {
+        self.fs
+    }
+
+    pub fn fft_size(&self) -> i32 {
+        self.fft_size
+    }
+
+    /// Number of frames emitted so far.
+    pub fn frames(&self) -> usize {
+        self.next_frame
+    }
+
+    /// Appends `x` and returns every frame that now has enough audio on both sides.
+    /// On error the analyzer is left as it was before the call.
+    pub fn push(&mut self, x: &[f64]) -> Result<Option<WorldParameters>> {
+        check_finite("x", x)?;
+        self.buffer.extend_from_slice(x);
+        self.received += x.len();
+        match self.analyze_ready() {
+            Ok(batch) => {
+                self.advance(self.next_frame + batch.f0.len());
+                batch.into_parameters(self)
+            },
+            Err(error) => {
+                self.buffer.truncate(self.buffer.len() - x.len());
+                self.received -= x.len();
+                Err(error)
+            },
+        }
+    }
+
+    fn analyze_ready(&self) -> Result<Batch> {
+        let mut batch = Batch::default();
+        let mut first = self.next_frame;
+        loop {
+            let last = first + self.chunk_frames - 1 + self.context_frames;
+            let end  = self.position(last).ceil() as usize + 1;
+            if end > self.received {
+                break;
+            }
+            self.analyze(first, self.chunk_frames, end, &mut batch)?;
+            first += self.chunk_frames;
+        }
+        Ok(batch)
+    }
+
+    /// Emits the remaining frames and resets the analyzer for a new stream.
+    pub fn finish(&mut self) -> Result<Option<WorldParameters>> {
+        let mut batch = Batch::default();
+        if self.received > 0 {
+            // Same count as GetSamplesForDIO/GetSamplesForHarvest on the whole signal.
+            let total = (1000.0 * self.received as f64 / self.fs as f64 / self.analyzer.period()) as usize + 1;
+            if total > self.next_frame {
+                self.analyze(self.next_frame, total - self.next_frame, self.received, &mut batch)?;
+            }
+        }
+        let parameters = batch.into_parameters(self)?.map(|parameters| {
+            // The last batch runs to the end of the signal rather than to a frame boundary.
+            let first = self.position(self.next_frame).round() as usize;
+            parameters.with_signal_length(self.received.saturating_sub(first))
+        });
+        self.buffer.clear();
+        self.buffer_origin = 0;
+        self.received      = 0;
+        self.next_frame    = 0;
+        Ok(parameters)
+    }
+
+    fn position(&self, frame: usize) -> f64 {
+        frame as f64 * self.analyzer.period() * self.fs as f64 / 1000.0
+    }
+
+    fn window_start(&self, frame: usize) -> (usize, usize) {
+        let start  = frame.saturating_sub(self.context_frames) / self.alignment * self.alignment;
+        // A multiple of the alignment, so a whole number of samples up to rounding.
+        let sample = self.position(start).round() as usize;
+        (start, sample.max(self.buffer_origin))
+    }
+
+    fn analyze(&self, first: usize, frames: usize, end: usize, batch: &mut Batch) -> Result<()> {
+        let (start, origin) = self.window_start(first);
+        let segment         = &self.buffer[origin-self.buffer_origin..end-self.buffer_origin];
+        let (_, f0)         = self.analyzer.estimate_f0(segment, self.fs)?;
+        let offset          = origin as f64 / self.fs as f64;
+        let frame_period    = self.analyzer.period();
+        let last_f0         = f0.last().copied().unwrap_or(0.0);
+        let mut temporal_positions = Vec::with_capacity(frames);
+        let mut local_positions    = Vec::with_capacity(frames);
+        let mut local_f0           = Vec::with_capacity(frames);
+        for frame in first..first+frames {
+            let time = frame as f64 * frame_period / 1000.0;
+            temporal_positions.push(time);
+            local_positions.push(time - offset);
+            local_f0.push(f0.get(frame - start).copied().unwrap_or(last_f0));
+        }
+        let spectrogram  = cheaptrick(segment, self.fs, &local_positions, &local_f0, &self.analyzer.cheaptrick_option())?;
+        let aperiodicity = d4c(segment, self.fs, &local_positions, &local_f0, self.fft_size, &self.analyzer.d4c_option())?;
+        batch.f0.extend_from_slice(&local_f0);
+        batch.temporal_positions.extend_from_slice(&temporal_positions);
+        batch.spectrogram.extend_from_slice(spectrogram.as_slice());
+        batch.aperiodicity.extend_from_slice(aperiodicity.as_slice());
+        Ok(())
+    }
+
+    // Moves past the frames emitted so far and drops the audio no window needs.
+    fn advance(&mut self, next_frame: usize) {
+        self.next_frame = next_frame;
+        let (_, keep)   = self.window_start(self.next_frame);
+        let keep        = keep.min(self.received);
+        self.buffer.drain(..keep-self.buffer_origin);
+        self.buffer_origin = keep;
+    }
+}
+
+#[derive(Default)]
+struct Batch {
+    f0:                 Vec<f64>,
+    temporal_positions: Vec<f64>,
+    spectrogram:        Vec<f64>,
+    aperiodicity:       Vec<f64>,
+}
+
+impl Batch {
+    fn into_parameters(self, analyzer: &StreamingAnalyzer) -> Result<Option<WorldParameters>> {
+        if self.f0.is_empty() {
+            return Ok(None);
+        }
+        let frames       = self.f0.len();
+        let bins         = analyzer.fft_size as usize / 2 + 1;
+        let spectrogram  = Spectrogram::from_vec(frames, bins, self.spectrogram)?;
+        let aperiodicity = Aperiodicity::from_vec(frames, bins, self.aperiodicity)?;
+        WorldParameters::new(self.f0, self.temporal_positions, spectrogram, aperiodicity, analyzer.fs, analyzer.analyzer.period()).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Analyzer, F0Method, StreamingAnalyzer, WorldParameters, WorldError};
+    use crate::test_signals::harmonics;
+
+    fn stream(analyzer: &Analyzer, x: &[f64], fs: i32, chunk: usize) -> Vec<WorldParameters> {
+        let mut streaming = StreamingAnalyzer::new(analyzer.clone(), fs).unwrap().chunk_frames(50);
+        let mut batches   = Vec::new();
+        for samples in x.chunks(chunk) {
+            batches.extend(streaming.push(samples).unwrap());
+        }
+        batches.extend(streaming.finish().unwrap());
+        batches
+    }
+
+    // Every frame of the streamed analysis matches a whole-file run, F0 in Hz,
+    // the spectrogram relative to the peak of its row and aperiodicity as is.
+    fn assert_same_as_whole_file(analyzer: &Analyzer, x: &[f64], fs: i32, tolerance: (f64, f64, f64)) {
+        let whole   = analyzer.analyze(x, fs).unwrap();
+        let batches = stream(analyzer, x, fs, 777);
+        assert!(batches.len() > 1);
+        let temporal_positions: Vec<f64> = batches.iter().flat_map(|batch| batch.temporal_positions().to_vec()).collect();
+        let f0:                 Vec<f64> = batches.iter().flat_map(|batch| batch.f0().to_vec()).collect();
+        let spectrogram:   Vec<Vec<f64>> = batches.iter().flat_map(|batch| batch.spectrogram().to_rows()).collect();
+        let aperiodicity:  Vec<Vec<f64>> = batches.iter().flat_map(|batch| batch.aperiodicity().to_rows()).collect();
+        assert_eq!(temporal_positions, whole.temporal_positions());
+        assert_eq!(spectrogram.len(),  whole.frames());
+        assert_eq!(aperiodicity.len(), whole.frames());
+        for frame in 0..whole.frames() {
+            let reference = whole.f0()[frame];
+            assert!((f0[frame] - reference).abs() <= tolerance.0, "frame {}: f0 {} != {}", frame, f0[frame], reference);
+            let peak = whole.spectrogram().row(frame).iter().fold(0.0f64, |peak, &v| peak.max(v));
+            for (streamed, reference) in spectrogram[frame].iter().zip(whole.spectrogram().row(frame)) {
+                assert!((streamed - reference).abs() <= tolerance.1 * peak, "frame {}: spectrogram {} != {}", frame, streamed, reference);
+            }
+            for (streamed, reference) in aperiodicity[frame].iter().zip(whole.aperiodicity().row(frame)) {
+                assert!((streamed - reference).abs() <= tolerance.2, "frame {}: aperiodicity {} != {}", frame, streamed, reference);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dio_same_as_whole_file() {
+        let fs = 16000;
+        assert_same_as_whole_file(&Analyzer::new().f0_method(F0Method::Dio), &harmonics(fs, 200.0, 1.5), fs, (1e-6, 1e-3, 1e-3));
+    }
+
+    #[test]
+    fn test_harvest_same_as_whole_file() {
+        // Harvest's filters depend on the length of the signal, so the
+        // estimates differ slightly more.
+        let fs = 16000;
+        assert_same_as_whole_file(&Analyzer::new(), &harmonics(fs, 200.0, 1.5), fs, (1.0, 2e-2, 5e-2));
+    }
+
+    #[test]
+    fn test_invalid_context_frames() {
+        let streaming = StreamingAnalyzer::new(Analyzer::new(), 16000).unwrap();
+        assert_eq!(streaming.context_frames(0).err(), Some(WorldError::InvalidOption { name: "context_frames", value: 0.0 }));
+    }
+
+    #[test]
+    fn test_unaligned_frame_period() {
+        // 80.00016 samples per frame take 6250 frames to span a whole number.
+        let analyzer = Analyzer::new().frame_period(5.00001);
+        assert_eq!(StreamingAnalyzer::new(analyzer, 16000).err(), Some(WorldError::InvalidOption { name: "frame_period", value: 5.00001 }));
+    }
+
+    #[test]
+    fn test_bounded_buffer() {
+        let fs            = 16000;
+        let mut streaming = StreamingAnalyzer::new(Analyzer::new().f0_method(F0Method::Dio), fs).unwrap().chunk_frames(20);
+        let x             = harmonics(fs, 150.0, 3.0);
+        for samples in x.chunks(1600) {
+            streaming.push(samples).unwrap();
+            assert!(streaming.buffer.len() < fs as usize);
+        }
+        let last = streaming.finish().unwrap().unwrap();
+        assert_eq!(last.temporal_positions().last(), Some(&3.0));
+        assert_eq!(streaming.frames(), 0);
+    }
+
+    #[test]
+    fn test_fractional_frame_period() {
+        let fs       = 44100;
+        let x        = harmonics(fs, 220.0, 0.5);
+        let analyzer = Analyzer::new().f0_method(F0Method::Dio);
+        let whole    = analyzer.analyze(&x, fs).unwrap();
+        let frames: usize = stream(&analyzer, &x, fs, 4096).iter().map(|batch| batch.frames()).sum();
+        assert_eq!(frames, whole.frames());
+    }
+
+    #[test]
+    fn test_empty_stream() {
+        let mut streaming = StreamingAnalyzer::new(Analyzer::new(), 16000).unwrap();
+        assert_eq!(streaming.push(&[]), Ok(None));
+        assert_eq!(streaming.finish(), Ok(None));
+    }
+}
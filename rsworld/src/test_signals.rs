@@ -0,0 +1,10 @@
+//! Signals shared by the unit tests.
+
+/// Ten equal-phase harmonics of `f0` with 1/k amplitudes.
+pub(crate) fn harmonics(fs: i32, f0: f64, seconds: f64) -> Vec<f64> {
+    let length = (fs as f64 * seconds) as usize;
+    (0..length).map(|i| {
+        let t = i as f64 / fs as f64;
+        (1..=10).map(|k| 0.1 / k as f64 * (2.0 * std::f64::consts::PI * f0 * k as f64 * t).sin()).sum()
+    }).collect()
+}
@@ -0,0 +1,232 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use hound::{WavReader, WavWriter, WavSpec, SampleFormat};
+
+use crate::error::{WorldError, Result, check_fs, check_finite};
+
+fn from_hound(error: hound::Error) -> WorldError {
+    match error {
+        hound::Error::IoError(error) => error.into(),
+        error                        => WorldError::InvalidFormat(error.to_string()),
+    }
+}
+
+/// Which channel of a multichannel file becomes the analysed signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelSelection {
+    /// Average of all channels.
+    #[default]
+    Downmix,
+    Channel(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavFormat {
+    Int16,
+    Int24,
+    Int32,
+    Float32,
+}
+
+impl WavFormat {
+    fn spec(self, fs: i32) -> WavSpec {
+        let (bits_per_sample, sample_format) = match self {
+            WavFormat::Int16   => (16, SampleFormat::Int),
+            WavFormat::Int24   => (24, SampleFormat::Int),
+            WavFormat::Int32   => (32, SampleFormat::Int),
+            WavFormat::Float32 => (32, SampleFormat::Float),
+        };
+        WavSpec { channels: 1, sample_rate: fs as u32, bits_per_sample, sample_format }
+    }
+}
+
+/// Settings for `write_wav`. Samples are expected in [-1.0, 1.0].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WavWriteOption {
+    pub format: WavFormat,
+    pub dither: bool,
+    pub seed:   u64,
+}
+
+impl Default for WavWriteOption {
+    fn default() -> Self {
+        WavWriteOption { format: WavFormat::Int16, dither: false, seed: 0 }
+    }
+}
+
+impl WavWriteOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn format(mut self, format: WavFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Adds ±1 LSB triangular dither before quantising integer formats.
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Seed of the dither noise, so that output is reproducible.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+// xorshift64*, enough for dither and keeps the feature dependency-free apart from hound.
+struct Dither(u64);
+
+impl Dither {
+    fn uniform(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn triangular(&mut self) -> f64 {
+        self.uniform() - self.uniform()
+    }
+}
+
+/// Reads a PCM (8 to 32 bit) or 32-bit float WAV file, scaled to [-1.0, 1.0). Returns `(x, fs)`.
+pub fn read_wav<P: AsRef<Path>>(path: P, channels: ChannelSelection) -> Result<(Vec<f64>, i32)> {
+    let reader = WavReader::open(path).map_err(from_hound)?;
+    read(reader, channels)
+}
+
+pub fn read_wav_from<R: Read>(reader: R, channels: ChannelSelection) -> Result<(Vec<f64>, i32)> {
+    let reader = WavReader::new(reader).map_err(from_hound)?;
+    read(reader, channels)
+}
+
+fn read<R: Read>(reader: WavReader<R>, selection: ChannelSelection) -> Result<(Vec<f64>, i32)> {
+    let spec     = reader.spec();
+    let channels = spec.channels as usize;
+    if let ChannelSelection::Channel(channel) = selection {
+        if channel >= channels {
+            return Err(WorldError::InvalidChannel { channel, channels });
+        }
+    }
+    let fs = spec.sample_rate as i32;
+    check_fs(fs)?;
+    let interleaved: Vec<f64> = match spec.sample_format {
+        SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f64;
+            reader.into_samples::<i32>().map(|sample| sample.map(|v| v as f64 / scale)).collect::<std::result::Result<_, _>>()
+        },
+        SampleFormat::Float => {
+            reader.into_samples::<f32>().map(|sample| sample.map(|v| v as f64)).collect::<std::result::Result<_, _>>()
+        },
+    }.map_err(from_hound)?;
+    let x = match selection {
+        ChannelSelection::Channel(channel) => interleaved.chunks_exact(channels).map(|frame| frame[channel]).collect(),
+        ChannelSelection::Downmix          => interleaved.chunks_exact(channels).map(|frame| frame.iter().sum::<f64>() / channels as f64).collect(),
+    };
+    Ok((x, fs))
+}
+
+/// Writes a mono WAV file and returns the number of samples that had to be clipped.
+pub fn write_wav<P: AsRef<Path>>(path: P, y: &[f64], fs: i32, option: &WavWriteOption) -> Result<usize> {
+    write_wav_to(BufWriter::new(File::create(path)?), y, fs, option)
+}
+
+pub fn write_wav_to<W: Write + Seek>(writer: W, y: &[f64], fs: i32, option: &WavWriteOption) -> Result<usize> {
+    check_fs(fs)?;
+    check_finite("y", y)?;
+    let spec       = option.format.spec(fs);
+    let mut writer = WavWriter::new(writer, spec).map_err(from_hound)?;
+    let mut dither = Dither(option.seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+    let mut clipped = 0;
+    for &v in y {
+        if option.format == WavFormat::Float32 {
+            if v.abs() > 1.0 {
+                clipped += 1;
+            }
+            writer.write_sample(v.clamp(-1.0, 1.0) as f32).map_err(from_hound)?;
+            continue;
+        }
+        let scale  = (1u64 << (spec.bits_per_sample - 1)) as f64;
+        let noise  = if option.dither { dither.triangular() } else { 0.0 };
+        let sample = (v * scale + noise).round();
+        // +1.0 is representable in floating point but not in two's complement.
+        if sample < -scale || sample > scale - 1.0 {
+            clipped += 1;
+        }
+        writer.write_sample(sample.clamp(-scale, scale - 1.0) as i32).map_err(from_hound)?;
+    }
+    writer.finalize().map_err(from_hound)?;
+    Ok(clipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use hound::{WavSpec, WavWriter, SampleFormat};
+
+    use crate::{read_wav_from, write_wav_to, ChannelSelection, WavFormat, WavWriteOption, WorldError};
+
+    fn round_trip(y: &[f64], option: &WavWriteOption) -> (Vec<f64>, i32, usize) {
+        let mut buffer = Cursor::new(Vec::new());
+        let clipped    = write_wav_to(&mut buffer, y, 16000, option).unwrap();
+        buffer.set_position(0);
+        let (x, fs) = read_wav_from(buffer, ChannelSelection::Downmix).unwrap();
+        (x, fs, clipped)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let y = vec![0.0, 0.5, -0.5, 0.25, -1.0];
+        for (format, step) in [(WavFormat::Int16, 1.0 / 32768.0), (WavFormat::Int24, 1.0 / 8388608.0), (WavFormat::Int32, 1e-9), (WavFormat::Float32, 1e-7)] {
+            let (x, fs, clipped) = round_trip(&y, &WavWriteOption::new().format(format));
+            assert_eq!(fs, 16000);
+            assert_eq!(clipped, 0);
+            for (x, y) in x.iter().zip(&y) {
+                assert!((x - y).abs() <= step, "{:?}: {} != {}", format, x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clipping() {
+        let (x, _, clipped) = round_trip(&[1.5, -2.0, 0.1, 1.0], &WavWriteOption::new());
+        assert_eq!(clipped, 3);
+        assert_eq!(x[0], 32767.0 / 32768.0);
+        assert_eq!(x[1], -1.0);
+    }
+
+    #[test]
+    fn test_dither() {
+        let y             = vec![0.3; 1000];
+        let option        = WavWriteOption::new().dither(true).seed(7);
+        let (x, _, _)     = round_trip(&y, &option);
+        let (again, _, _) = round_trip(&y, &option);
+        assert_eq!(x, again);
+        assert!(x.iter().any(|v| *v != x[0]));
+        let mean = x.iter().sum::<f64>() / x.len() as f64;
+        assert!((mean - 0.3).abs() < 3e-6);
+    }
+
+    #[test]
+    fn test_channels() {
+        let spec       = WavSpec { channels: 2, sample_rate: 8000, bits_per_sample: 16, sample_format: SampleFormat::Int };
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = WavWriter::new(&mut buffer, spec).unwrap();
+        for sample in [16384i16, 0, -16384, 8192] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        let bytes = buffer.into_inner();
+        assert_eq!(read_wav_from(Cursor::new(&bytes), ChannelSelection::Downmix),    Ok((vec![0.25, -0.125], 8000)));
+        assert_eq!(read_wav_from(Cursor::new(&bytes), ChannelSelection::Channel(1)), Ok((vec![0.0, 0.25], 8000)));
+        assert_eq!(read_wav_from(Cursor::new(&bytes), ChannelSelection::Channel(2)),
+                   Err(WorldError::InvalidChannel { channel: 2, channels: 2 }));
+    }
+}
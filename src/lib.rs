@@ -1,2 +1,5 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "ffi")]
 pub use rsworld_sys;
 pub use rsworld;